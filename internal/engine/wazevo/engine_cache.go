@@ -42,6 +42,11 @@ func fileCacheKey(m *wasm.Module) (ret filecache.Key) {
 	return
 }
 
+// FileCacheKey returns the key of the given module in the file cache. This is exported for testing purpose.
+func FileCacheKey(m *wasm.Module) filecache.Key {
+	return fileCacheKey(m)
+}
+
 func (e *engine) addCompiledModule(module *wasm.Module, cm *compiledModule) (err error) {
 	e.addCompiledModuleToMemory(module, cm)
 	if !module.IsHostModule && e.fileCache != nil {
@@ -0,0 +1,28 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |_| {})?;
+    // The remaining bytes are decoded as the arguments of the repeated invocations of the exported functions.
+    let args = u.take_rest();
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_no_diff_with_args(
+            module_bytes.as_ptr(),
+            module_bytes.len(),
+            args.as_ptr(),
+            args.len(),
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use wasm_smith::Config;
+
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    // Generate two modules from the same configuration so that they are likely to be similar.
+    let config = Config::arbitrary(&mut u)?;
+    let first = wasm_smith::Module::new(config.clone(), &mut u)?.to_bytes();
+    let second = wasm_smith::Module::new(config, &mut u)?.to_bytes();
+
+    // Skip the modules which wazero cannot decode as they are never compiled.
+    let (first_key, _) = match util::cache_key_of(&first, false, false) {
+        Some(v) => v,
+        None => return Ok(()),
+    };
+
+    // Deriving the key for the same module must be stable.
+    if util::cache_key_of(&first, false, false) != Some((first_key, 0)) {
+        panic!("cache key is not stable for the same module");
+    }
+
+    // The configuration affecting the compilation must be reflected in the key.
+    let (terminating_key, _) = util::cache_key_of(&first, false, true).unwrap();
+    if first_key == terminating_key {
+        panic!("cache key does not depend on ensure_termination");
+    }
+    let (listening_key, listener_count) = util::cache_key_of(&first, true, false).unwrap();
+    if listener_count > 0 && first_key == listening_key {
+        panic!("cache key does not depend on the function listeners");
+    }
+    let (both_key, _) = util::cache_key_of(&first, true, true).unwrap();
+    if listener_count > 0 && (both_key == terminating_key || both_key == listening_key) {
+        panic!("cache key does not depend on the combination of the configuration");
+    }
+
+    // Different modules must never share the key, while the identical ones always must.
+    let second_key = match util::cache_key_of(&second, false, false) {
+        Some((key, _)) => key,
+        None => return Ok(()),
+    };
+    if first == second && first_key != second_key {
+        panic!("cache keys differ for the identical modules");
+    } else if first != second && first_key == second_key {
+        panic!(
+            "cache key collision between different modules:\n\t{:?}\n\t{:?}",
+            first, second
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |_| {})?;
+
+    // Inject the arbitrary custom sections at the arbitrary section boundaries.
+    let mut injected = Vec::with_capacity(module_bytes.len());
+    let mut start = 0;
+    for boundary in util::section_boundaries(&module_bytes) {
+        injected.extend_from_slice(&module_bytes[start..boundary]);
+        start = boundary;
+        while u.ratio(1, 4)? {
+            write_custom_section(&mut u, &mut injected)?;
+        }
+    }
+
+    // Pass the module with the injected custom sections to the wazero library.
+    unsafe {
+        util::require_no_diff_custom_sections(injected.as_ptr(), injected.len());
+    }
+    Ok(())
+}
+
+/// Appends the arbitrary custom section to buf.
+fn write_custom_section(u: &mut Unstructured, buf: &mut Vec<u8>) -> Result<()> {
+    let mut contents = Vec::new();
+    match u.int_in_range(0..=3)? {
+        0 => {
+            // The well-formed name section with only the module name subsection.
+            write_name(&mut contents, "name");
+            let mut subsection = Vec::new();
+            write_name(&mut subsection, u.arbitrary()?);
+            contents.push(0);
+            util::write_leb128_u32(&mut contents, subsection.len() as u32);
+            contents.extend_from_slice(&subsection);
+        }
+        1 => {
+            // The well-known sections with the arbitrary, likely malformed, contents.
+            write_name(&mut contents, *u.choose(&["name", "producers"])?);
+            contents.extend_from_slice(u.arbitrary()?);
+        }
+        _ => {
+            write_name(&mut contents, u.arbitrary()?);
+            contents.extend_from_slice(u.arbitrary()?);
+        }
+    }
+
+    // Custom sections have the id zero.
+    buf.push(0);
+    util::write_leb128_u32(buf, contents.len() as u32);
+    buf.extend_from_slice(&contents);
+    Ok(())
+}
+
+/// Appends the name encoded as the length-prefixed UTF-8 bytes to buf.
+fn write_name(buf: &mut Vec<u8>, name: &str) {
+    util::write_leb128_u32(buf, name.len() as u32);
+    buf.extend_from_slice(name.as_bytes());
+}
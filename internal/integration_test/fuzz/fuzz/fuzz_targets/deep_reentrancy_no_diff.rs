@@ -0,0 +1,32 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    // The default fuel is shared by all the nested calls, and is below the call stack ceiling of the interpreter,
+    // so the guest runs out of fuel rather than stack regardless of the engine.
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // Always import the re-entrant host functions.
+        config.available_imports = Some(util::REENTRANT_HOST_IMPORTS.to_vec());
+        config.min_imports = 1;
+        config.max_imports = config.max_imports.max(2);
+        // With only a few functions, the host functions are likely to be called recursively.
+        config.max_funcs = 3;
+        // Keep each function small so that the time is spent on the calls rather than the loops.
+        config.max_instructions = 100;
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_no_diff_deep_reentrant(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // The module is instantiated alone many times.
+        config.max_imports = 0;
+    })?;
+    let seed: i64 = u.arbitrary()?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_deterministic_exports(module_bytes.as_ptr(), module_bytes.len(), seed);
+    }
+    Ok(())
+}
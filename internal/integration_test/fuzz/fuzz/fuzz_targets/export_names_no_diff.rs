@@ -0,0 +1,84 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+/// The unusual but valid export names. None of them contains '#', which is used for making the names unique.
+const EXPORT_NAMES: &[&str] = &[
+    "",
+    " ",
+    "\0",
+    "\n",
+    "é",
+    "日本語",
+    "🦀",
+    "\u{200b}",
+    "\u{feff}",
+    "\u{10ffff}",
+    "e\u{301}",
+];
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // The logging is compared as well, which prints the export names.
+        config.reference_types_enabled = false;
+    })?;
+    let offset = u.choose_index(EXPORT_NAMES.len())?;
+
+    // Rename all the exports in the export section, whose id is 7.
+    let mut renamed = Vec::with_capacity(module_bytes.len());
+    let boundaries = util::section_boundaries(&module_bytes);
+    renamed.extend_from_slice(&module_bytes[..boundaries[0]]);
+    for window in boundaries.windows(2) {
+        let section = &module_bytes[window[0]..window[1]];
+        if section[0] == 7 {
+            let (size, size_len) = util::read_leb128_u32(&section[1..]);
+            let contents = rename_exports(&section[1 + size_len..][..size as usize], offset);
+            renamed.push(7);
+            util::write_leb128_u32(&mut renamed, contents.len() as u32);
+            renamed.extend_from_slice(&contents);
+        } else {
+            renamed.extend_from_slice(section);
+        }
+    }
+
+    // Pass the module with the renamed exports to the wazero library.
+    unsafe {
+        util::require_no_diff(renamed.as_ptr(), renamed.len(), false, true, true);
+    }
+    Ok(())
+}
+
+/// Returns the contents of the export section with each export renamed to the one in EXPORT_NAMES starting at offset.
+fn rename_exports(contents: &[u8], offset: usize) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(contents.len());
+    let (count, mut pos) = util::read_leb128_u32(contents);
+    util::write_leb128_u32(&mut ret, count);
+    for i in 0..count as usize {
+        // Skip the original name.
+        let (name_len, name_len_len) = util::read_leb128_u32(&contents[pos..]);
+        pos += name_len_len + name_len as usize;
+
+        let base = EXPORT_NAMES[(i + offset) % EXPORT_NAMES.len()];
+        let name = if i < EXPORT_NAMES.len() {
+            base.to_string()
+        } else {
+            format!("{}#{}", base, i)
+        };
+        util::write_leb128_u32(&mut ret, name.len() as u32);
+        ret.extend_from_slice(name.as_bytes());
+
+        // Copy the kind and the index as-is.
+        let (_, index_len) = util::read_leb128_u32(&contents[pos + 1..]);
+        ret.extend_from_slice(&contents[pos..pos + 1 + index_len]);
+        pos += 1 + index_len;
+    }
+    ret
+}
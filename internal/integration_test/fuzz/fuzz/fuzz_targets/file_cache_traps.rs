@@ -0,0 +1,24 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // The module is instantiated by itself without any importable modules.
+        config.max_imports = 0;
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_same_traps_after_reload(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
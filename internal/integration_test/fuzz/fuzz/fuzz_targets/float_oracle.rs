@@ -0,0 +1,36 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use wasm_smith::{InstructionKind, InstructionKinds};
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // Focus on the arithmetic so that most of the results are the floating point computations.
+        config.allowed_instructions = InstructionKinds::new(&[
+            InstructionKind::Numeric,
+            InstructionKind::Vector,
+            InstructionKind::Parametric,
+            InstructionKind::Variable,
+            InstructionKind::Control,
+        ]);
+        config.simd_enabled = true;
+        // Relaxed SIMD is non-deterministic by design, so it must not be used here.
+        config.relaxed_simd_enabled = false;
+        // The oracle is produced by instantiating the module alone.
+        config.max_imports = 0;
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_float_oracle(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+/// The maximum number of the instances of a module alive at the same time.
+const MAX_INSTANCES: isize = 8;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // The module is instantiated by itself without any importable modules.
+        config.max_imports = 0;
+        // Give the instances some state to mutate in addition to the memory.
+        config.min_globals = config.min_globals.max(1);
+        config.max_globals = config.max_globals.max(config.min_globals);
+    })?;
+    let instances = u.int_in_range(2..=MAX_INSTANCES)?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_isolated_instances(module_bytes.as_ptr(), module_bytes.len(), instances);
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // The exported globals are imported by another module, so the module itself must be instantiable alone.
+        config.max_imports = 0;
+        config.min_globals = config.min_globals.max(1);
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_no_diff_linked_globals(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
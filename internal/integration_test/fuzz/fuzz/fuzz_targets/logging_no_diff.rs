@@ -3,5 +3,5 @@ use libfuzzer_sys::fuzz_target;
 mod util;
 
 fuzz_target!(|data: &[u8]| {
-    let _ = util::run_nodiff(data, false, true);
+    let _ = util::run_nodiff(data, false, true, false);
 });
@@ -0,0 +1,36 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // Generate hundreds of functions and types.
+        config.min_funcs = 200;
+        config.max_funcs = config.max_funcs.max(500);
+        config.min_types = 100;
+        config.max_types = config.max_types.max(300);
+        // Keep each function small so that the time is dominated by the number of them.
+        config.max_instructions = 100;
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_compile_within(module_bytes.as_ptr(), module_bytes.len());
+        util::require_no_diff(
+            module_bytes.as_ptr(),
+            module_bytes.len(),
+            false,
+            false,
+            false,
+        );
+    }
+    Ok(())
+}
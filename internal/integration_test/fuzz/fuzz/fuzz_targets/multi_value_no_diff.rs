@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = util::run_nodiff_with_config(data, false, false, false, |config| {
+        // Always allow blocks and functions to have multiple parameters and results.
+        config.multi_value_enabled = true;
+        // Ensures that a reasonable number of distinct signatures are available for blocks and functions.
+        config.min_types = config.min_types.max(10);
+        config.max_types = config.max_types.max(config.min_types);
+        // Multi-value blocks are mostly found in the nested control flows.
+        config.max_nesting_depth = config.max_nesting_depth.max(10);
+    });
+});
@@ -0,0 +1,75 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+/// The size of the magic number and version, which are never mutated.
+const HEADER_SIZE: usize = 8;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    // Start from the valid module, and apply a few small mutations so that it is likely to be near-valid.
+    let mut module_bytes = util::generate_module(&mut u, |_| {})?;
+    // The structural mutation comes first as it relies on the section framing of the valid module.
+    let truncated = u.arbitrary()?;
+    if truncated {
+        module_bytes = truncate_section(&mut u, &module_bytes)?;
+    }
+    let min_byte_mutations = if truncated { 0 } else { 1 };
+    for _ in 0..u.int_in_range(min_byte_mutations..=3)? {
+        mutate_byte(&mut u, &mut module_bytes)?;
+    }
+
+    unsafe {
+        util::require_same_validation(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
+
+/// Truncates or removes a section while keeping the framing of the other sections intact.
+fn truncate_section(u: &mut Unstructured, module_bytes: &[u8]) -> Result<Vec<u8>> {
+    let boundaries = util::section_boundaries(module_bytes);
+    if boundaries.len() < 2 {
+        return Ok(module_bytes.to_vec());
+    }
+    let i = u.choose_index(boundaries.len() - 1)?;
+    let (start, end) = (boundaries[i], boundaries[i + 1]);
+
+    let mut mutated = module_bytes[..start].to_vec();
+    if u.arbitrary()? {
+        let id = module_bytes[start];
+        let (size, size_len) = util::read_leb128_u32(&module_bytes[start + 1..]);
+        let contents_start = start + 1 + size_len;
+        let new_size = u.int_in_range(0..=size)?;
+        mutated.push(id);
+        util::write_leb128_u32(&mut mutated, new_size);
+        let contents = &module_bytes[contents_start..];
+        mutated.extend_from_slice(&contents[..new_size as usize]);
+    }
+    mutated.extend_from_slice(&module_bytes[end..]);
+    Ok(mutated)
+}
+
+/// Mutates a byte after the header.
+fn mutate_byte(u: &mut Unstructured, module_bytes: &mut [u8]) -> Result<()> {
+    if module_bytes.len() <= HEADER_SIZE {
+        return Ok(());
+    }
+    let i = u.int_in_range(HEADER_SIZE..=module_bytes.len() - 1)?;
+    if u.arbitrary()? {
+        // Flip the arbitrary bits of the byte.
+        module_bytes[i] ^= u.int_in_range(1..=u8::MAX)?;
+    } else if u.arbitrary()? {
+        // Nudge the byte by one, e.g. to shift a type or function index to its neighbor.
+        module_bytes[i] = module_bytes[i].wrapping_add(1);
+    } else {
+        module_bytes[i] = module_bytes[i].wrapping_sub(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // Only import the re-entrant host functions.
+        config.available_imports = Some(util::REENTRANT_HOST_IMPORTS.to_vec());
+        config.max_imports = config.max_imports.max(2);
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_no_diff_reentrant(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
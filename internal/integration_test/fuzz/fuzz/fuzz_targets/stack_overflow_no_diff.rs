@@ -0,0 +1,35 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+/// The fuel large enough for the recursion to exhaust the stack before running out of it.
+const FUEL: u32 = 1 << 20;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module_with_fuel(&mut u, FUEL, |config| {
+        // With only a few functions, the calls are likely to be recursive.
+        config.max_funcs = 3;
+        // Keep each function small so that the time is spent on the calls rather than the loops.
+        config.max_instructions = 100;
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        util::require_no_diff(
+            module_bytes.as_ptr(),
+            module_bytes.len(),
+            false,
+            false,
+            false,
+        );
+    }
+    Ok(())
+}
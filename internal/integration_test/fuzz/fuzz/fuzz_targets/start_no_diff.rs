@@ -0,0 +1,44 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+/// The id of the start section.
+const START_SECTION_ID: u8 = 8;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // Let wasm-smith choose the start function whenever possible.
+        config.allow_start_export = true;
+        // Give the start function some state to mutate in addition to the memory.
+        config.min_globals = config.min_globals.max(5);
+        config.max_globals = config.max_globals.max(config.min_globals);
+        config.min_data_segments = config.min_data_segments.max(1);
+        config.max_data_segments = config.max_data_segments.max(config.min_data_segments);
+    })?;
+
+    // wasm-smith only chooses the start function when there's a function of type [] -> [], so skip the modules
+    // without it as they are already covered by memory_no_diff.
+    let boundaries = util::section_boundaries(&module_bytes);
+    let has_start = boundaries[..boundaries.len() - 1]
+        .iter()
+        .any(|&offset| module_bytes[offset] == START_SECTION_ID);
+    if !has_start {
+        return Ok(());
+    }
+
+    // The memory is compared after the invocations so that the side effects of the start function are
+    // observed together with those of the exported functions. The traps in the start function are
+    // compared as instantiation errors.
+    unsafe {
+        util::require_no_diff(module_bytes.as_ptr(), module_bytes.len(), true, false, true);
+    }
+    Ok(())
+}
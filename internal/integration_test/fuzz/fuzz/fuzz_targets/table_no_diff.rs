@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = util::run_nodiff_with_config(data, false, false, true, |config| {
+        // table.grow, table.fill and friends are only available with these proposals.
+        config.reference_types_enabled = true;
+        config.bulk_memory_enabled = true;
+        // Always have multiple tables populated by many element segments.
+        config.min_tables = 2;
+        config.max_tables = 4;
+        config.min_element_segments = 4;
+        config.max_element_segments = config.max_element_segments.max(20);
+        // Ensures that a reasonable number of distinct signatures are available for call_indirect.
+        config.min_types = config.min_types.max(10);
+        config.max_types = config.max_types.max(config.min_types);
+        config.min_funcs = config.min_funcs.max(10);
+        config.max_funcs = config.max_funcs.max(config.min_funcs);
+    });
+});
@@ -0,0 +1,31 @@
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+
+mod util;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(data);
+});
+
+fn run(data: &[u8]) -> Result<()> {
+    // Create the random source.
+    let mut u = Unstructured::new(data);
+
+    let module_bytes = util::generate_module(&mut u, |config| {
+        // Bulk memory operations can trap after partially writing the memory.
+        config.bulk_memory_enabled = true;
+        // Keep the memory small so that the accesses are likely out of bounds.
+        config.max_memory32_pages = 1;
+        // Populate the memory so that the partial writes are distinguishable.
+        config.min_data_segments = config.min_data_segments.max(1);
+        config.max_data_segments = config.max_data_segments.max(config.min_data_segments);
+    })?;
+
+    // The memory is compared right after each trapping invocation as well as at the end.
+    unsafe {
+        util::require_no_diff_after_trap(module_bytes.as_ptr(), module_bytes.len());
+    }
+    Ok(())
+}
@@ -7,11 +7,87 @@ extern "C" {
         binary_size: usize,
         check_memory: bool,
         check_logging: bool,
+        check_trap_reason: bool,
     );
 
+    // require_no_diff_after_trap is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_no_diff_after_trap(binary_ptr: *const u8, binary_size: usize);
+
+    // require_no_diff_with_args is implemented in Go, and accepts the pointer to the binary, its size,
+    // and the pointer to the bytes the arguments of the invocations are decoded from and its size.
+    #[allow(dead_code)]
+    pub fn require_no_diff_with_args(
+        binary_ptr: *const u8,
+        binary_size: usize,
+        args_ptr: *const u8,
+        args_size: usize,
+    );
+
+    // require_no_diff_reentrant is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_no_diff_reentrant(binary_ptr: *const u8, binary_size: usize);
+
+    // require_no_diff_deep_reentrant is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_no_diff_deep_reentrant(binary_ptr: *const u8, binary_size: usize);
+
+    // require_no_diff_custom_sections is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_no_diff_custom_sections(binary_ptr: *const u8, binary_size: usize);
+
     // validate is implemented in Go, and accepts the pointer to the binary and its size.
     #[allow(dead_code)]
     pub fn validate(binary_ptr: *const u8, binary_size: usize);
+
+    // require_same_validation is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_same_validation(binary_ptr: *const u8, binary_size: usize);
+
+    // require_deterministic_exports is implemented in Go, and accepts the pointer to the binary, its size,
+    // and the seed to shuffle the order of invocations.
+    #[allow(dead_code)]
+    pub fn require_deterministic_exports(binary_ptr: *const u8, binary_size: usize, seed: i64);
+
+    // require_no_diff_linked_globals is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_no_diff_linked_globals(binary_ptr: *const u8, binary_size: usize);
+
+    // require_isolated_instances is implemented in Go, and accepts the pointer to the binary, its size,
+    // and the number of the instances of the module alive at the same time.
+    #[allow(dead_code)]
+    pub fn require_isolated_instances(binary_ptr: *const u8, binary_size: usize, instances: isize);
+
+    // require_same_traps_after_reload is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_same_traps_after_reload(binary_ptr: *const u8, binary_size: usize);
+
+    // require_isolated_runtimes is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_isolated_runtimes(binary_ptr: *const u8, binary_size: usize);
+
+    // require_float_oracle is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_float_oracle(binary_ptr: *const u8, binary_size: usize);
+
+    // require_compile_within is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_compile_within(binary_ptr: *const u8, binary_size: usize);
+
+    // require_code_size_within is implemented in Go, and accepts the pointer to the binary and its size.
+    #[allow(dead_code)]
+    pub fn require_code_size_within(binary_ptr: *const u8, binary_size: usize);
+
+    // cache_key is implemented in Go, and writes the file cache key of the binary into the 32-byte buffer.
+    // Returns the number of the functions to which the listeners are attached, or -1 if the binary cannot be decoded.
+    #[allow(dead_code)]
+    pub fn cache_key(
+        binary_ptr: *const u8,
+        binary_size: usize,
+        with_listeners: bool,
+        ensure_termination: bool,
+        key_ptr: *mut u8,
+    ) -> isize;
 }
 
 use arbitrary::Arbitrary;
@@ -23,6 +99,97 @@ use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal
 use std::ptr::null_mut;
 use wasm_smith::Config;
 
+/// Returns the offsets of the section boundaries in the given module binary, which are the beginning
+/// of each section and the end of the binary.
+#[allow(dead_code)]
+pub fn section_boundaries(module_bytes: &[u8]) -> Vec<usize> {
+    // Skip the magic number and version.
+    let mut offset = 8;
+    let mut boundaries = vec![offset];
+    while offset < module_bytes.len() {
+        // Skip the section id.
+        offset += 1;
+        let (size, size_len) = read_leb128_u32(&module_bytes[offset..]);
+        offset += size_len + size as usize;
+        boundaries.push(offset);
+    }
+    boundaries
+}
+
+/// Reads the unsigned LEB128 encoded u32, and returns it with the number of bytes read.
+#[allow(dead_code)]
+pub fn read_leb128_u32(bytes: &[u8]) -> (u32, usize) {
+    let mut value = 0u32;
+    for (i, b) in bytes.iter().take(5).enumerate() {
+        value |= ((b & 0x7f) as u32) << (7 * i);
+        if b & 0x80 == 0 {
+            return (value, i + 1);
+        }
+    }
+    panic!("malformed LEB128: {:?}", &bytes[..bytes.len().min(5)]);
+}
+
+/// Appends the unsigned LEB128 encoded value to buf.
+#[allow(dead_code)]
+pub fn write_leb128_u32(buf: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let b = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(b);
+            return;
+        }
+        buf.push(b | 0x80);
+    }
+}
+
+/// The size of the compilation cache key, which is the sha256 checksum.
+#[allow(dead_code)]
+pub const CACHE_KEY_SIZE: usize = 32;
+
+/// Returns the file cache key derived by wazero for the given binary together with the number of the functions
+/// to which the listeners are attached, or None if wazero cannot decode the binary.
+#[allow(dead_code)]
+pub fn cache_key_of(
+    module_bytes: &[u8],
+    with_listeners: bool,
+    ensure_termination: bool,
+) -> Option<([u8; CACHE_KEY_SIZE], isize)> {
+    let mut key = [0u8; CACHE_KEY_SIZE];
+    let listener_count = unsafe {
+        cache_key(
+            module_bytes.as_ptr(),
+            module_bytes.len(),
+            with_listeners,
+            ensure_termination,
+            key.as_mut_ptr(),
+        )
+    };
+    if listener_count < 0 {
+        None
+    } else {
+        Some((key, listener_count))
+    }
+}
+
+/// The module whose imports are made available to the generated modules via `available_imports`.
+/// The functions are implemented by the "host" module in Go, which re-enters the guest:
+///
+/// (module
+///   (import "host" "reenter" (func (param i32) (result i32)))
+///   (import "host" "reenter_void" (func (param i32))))
+#[allow(dead_code)]
+pub const REENTRANT_HOST_IMPORTS: &[u8] = &[
+    // Magic number and version.
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+    // Type section with two function types.
+    0x01, 0x0a, 0x02, 0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x01, 0x7f, 0x00,
+    // Import section with two function imports.
+    0x02, 0x24, 0x02, 0x04, b'h', b'o', b's', b't', 0x07, b'r', b'e', b'e', b'n', b't', b'e', b'r',
+    0x00, 0x00, 0x04, b'h', b'o', b's', b't', 0x0c, b'r', b'e', b'e', b'n', b't', b'e', b'r', b'_',
+    b'v', b'o', b'i', b'd', 0x00, 0x01,
+];
+
 #[ctor]
 /// Sets up the separate stack for signal handlers, and sets the SA_ONSTACK flag for signals that are handled by libFuzzer
 ///  https://github.com/llvm/llvm-project/blob/8eff5704829ba5edd28754fd9ec7665b34fde22a/compiler-rt/lib/fuzzer/FuzzerUtilPosix.cpp#L117-L141
@@ -88,12 +255,64 @@ pub fn run_nodiff(
     data: &[u8],
     check_memory: bool,
     check_logging: bool,
+    check_trap_reason: bool,
+) -> libfuzzer_sys::arbitrary::Result<()> {
+    run_nodiff_with_config(data, check_memory, check_logging, check_trap_reason, |_| {})
+}
+
+/// Same as `run_nodiff` except that `configure` is applied to the generated configuration
+/// right before generating the module, so that targets can bias the generated modules.
+#[allow(dead_code)]
+pub fn run_nodiff_with_config(
+    data: &[u8],
+    check_memory: bool,
+    check_logging: bool,
+    check_trap_reason: bool,
+    configure: impl FnOnce(&mut Config),
 ) -> libfuzzer_sys::arbitrary::Result<()> {
     // Create the random source.
     let mut u = Unstructured::new(data);
 
+    let module_bytes = generate_module(&mut u, |config| {
+        if check_logging {
+            config.reference_types_enabled = false;
+        }
+        configure(config);
+    })?;
+
+    // Pass the randomly generated module to the wazero library.
+    unsafe {
+        require_no_diff(
+            module_bytes.as_ptr(),
+            module_bytes.len(),
+            check_memory,
+            check_logging,
+            check_trap_reason,
+        );
+    }
+    Ok(())
+}
+
+/// Generates the random module with the configuration shared among the no-diff targets.
+/// `configure` is applied to the configuration right before generating the module.
+#[allow(dead_code)]
+pub fn generate_module(
+    u: &mut Unstructured,
+    configure: impl FnOnce(&mut Config),
+) -> libfuzzer_sys::arbitrary::Result<Vec<u8>> {
+    generate_module_with_fuel(u, 1000, configure)
+}
+
+/// Same as `generate_module` except that `fuel` is used for ensuring the termination of the generated module,
+/// which bounds the number of the loop iterations and function calls in an invocation.
+#[allow(dead_code)]
+pub fn generate_module_with_fuel(
+    u: &mut Unstructured,
+    fuel: u32,
+    configure: impl FnOnce(&mut Config),
+) -> libfuzzer_sys::arbitrary::Result<Vec<u8>> {
     // Generate the configuration.
-    let mut config = Config::arbitrary(&mut u)?;
+    let mut config = Config::arbitrary(u)?;
 
     // 64-bit memory won't be supported by wazero.
     config.memory64_enabled = false;
@@ -124,23 +343,10 @@ pub fn run_nodiff(
     // Enable threads, which is disabled by default.
     config.threads_enabled = true;
 
-    if check_logging {
-        config.reference_types_enabled = false;
-    }
+    configure(&mut config);
 
     // Generate the random module via wasm-smith.
-    let mut module = wasm_smith::Module::new(config.clone(), &mut u)?;
-    module.ensure_termination(1000).unwrap();
-    let module_bytes = module.to_bytes();
-
-    // Pass the randomly generated module to the wazero library.
-    unsafe {
-        require_no_diff(
-            module_bytes.as_ptr(),
-            module_bytes.len(),
-            check_memory,
-            check_logging,
-        );
-    }
-    Ok(())
+    let mut module = wasm_smith::Module::new(config, u)?;
+    module.ensure_termination(fuel).unwrap();
+    Ok(module.to_bytes())
 }
@@ -0,0 +1,68 @@
+package main
+
+import (
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/leb128"
+	"github.com/tetratelabs/wazero/internal/wasm"
+	"github.com/tetratelabs/wazero/internal/wasm/binary"
+)
+
+// argsCustomSectionName is the name of the custom section which carries the arguments of the invocations
+// in the saved failed binary, so that the failure can be reproduced from the binary alone.
+const argsCustomSectionName = "wazero-fuzz-args"
+
+// instancesCustomSectionName is the name of the custom section which carries the number of the instances
+// in the saved failed binary of require_isolated_instances, encoded in unsigned LEB128.
+const instancesCustomSectionName = "wazero-fuzz-instances"
+
+// withArgsSection returns the copy of wasmBin with the custom section holding args appended.
+func withArgsSection(wasmBin, args []byte) []byte {
+	return withCustomSection(wasmBin, argsCustomSectionName, args)
+}
+
+// argsFromSection returns the arguments saved by withArgsSection, or nil if wasmBin doesn't have them.
+func argsFromSection(wasmBin []byte) []byte {
+	return customSectionData(wasmBin, argsCustomSectionName)
+}
+
+// withInstancesSection returns the copy of wasmBin with the custom section holding the number of the instances appended.
+func withInstancesSection(wasmBin []byte, instances int) []byte {
+	return withCustomSection(wasmBin, instancesCustomSectionName, leb128.EncodeUint32(uint32(instances)))
+}
+
+// instancesFromSection returns the number of the instances saved by withInstancesSection, or false if wasmBin doesn't
+// have it.
+func instancesFromSection(wasmBin []byte) (instances int, ok bool) {
+	data := customSectionData(wasmBin, instancesCustomSectionName)
+	if data == nil {
+		return 0, false
+	}
+	v, _, err := leb128.LoadUint32(data)
+	return int(v), err == nil
+}
+
+// withCustomSection returns the copy of wasmBin with the custom section of the given name and data appended.
+func withCustomSection(wasmBin []byte, name string, data []byte) []byte {
+	encodedName := append(leb128.EncodeUint32(uint32(len(name))), name...)
+	ret := make([]byte, 0, len(wasmBin)+len(encodedName)+len(data)+6)
+	ret = append(ret, wasmBin...)
+	ret = append(ret, wasm.SectionIDCustom)
+	ret = append(ret, leb128.EncodeUint32(uint32(len(encodedName)+len(data)))...)
+	ret = append(ret, encodedName...)
+	return append(ret, data...)
+}
+
+// customSectionData returns the data of the custom section of the given name, or nil if wasmBin doesn't have it.
+func customSectionData(wasmBin []byte, name string) []byte {
+	m, err := binary.DecodeModule(wasmBin, api.CoreFeaturesV2|experimental.CoreFeaturesThreads, wasm.MemoryLimitPages, false, false, true)
+	if err != nil {
+		return nil
+	}
+	for _, s := range m.CustomSections {
+		if s.Name == name {
+			return s.Data
+		}
+	}
+	return nil
+}
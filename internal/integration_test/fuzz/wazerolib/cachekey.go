@@ -0,0 +1,39 @@
+package main
+
+import (
+	"context"
+
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/engine/wazevo"
+	"github.com/tetratelabs/wazero/internal/filecache"
+	"github.com/tetratelabs/wazero/internal/wasm"
+	"github.com/tetratelabs/wazero/internal/wasm/binary"
+)
+
+// noopListener is attached to every function when the key is derived with the listeners.
+var noopListener = experimental.FunctionListenerFunc(
+	func(context.Context, api.Module, api.FunctionDefinition, []uint64, experimental.StackIterator) {})
+
+// cacheKey derives the key of the binary in the file cache the same way as the compiler engine does, i.e. the
+// module ID assigned by the runtime is rehashed with the engine specific magic and the CPU features.
+// This also returns the number of the functions to which the listeners are attached, which is zero when
+// withListeners is false, and false if the binary cannot be decoded.
+func cacheKey(wasmBin []byte, withListeners, ensureTermination bool) (key filecache.Key, listenerCount int, ok bool) {
+	m, err := binary.DecodeModule(wasmBin, api.CoreFeaturesV2|experimental.CoreFeaturesThreads,
+		wasm.MemoryLimitPages, false, false, false)
+	if err != nil {
+		return
+	}
+
+	// This mirrors how the runtime builds the listeners from the factory.
+	var listeners []experimental.FunctionListener
+	if withListeners {
+		listeners = make([]experimental.FunctionListener, len(m.FunctionSection))
+		for i := range listeners {
+			listeners[i] = noopListener
+		}
+	}
+	m.AssignModuleID(wasmBin, listeners, ensureTermination)
+	return wazevo.FileCacheKey(m), len(listeners), true
+}
@@ -0,0 +1,86 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+)
+
+// codeSizeMaxRatio is the maximum ratio of the size of the compiled module to that of the binary used by
+// require_code_size_within. The machine code is typically several times larger than the binary, so exceeding this
+// indicates the generated code blowing up.
+const codeSizeMaxRatio = 64
+
+// codeSizeSlack is the allowance for the fixed-size parts of the compiled module, such as the trampolines and the
+// header of the cache entry, which dominate the size of tiny modules.
+const codeSizeSlack = 64 << 10
+
+// requireCodeSizeWithin ensures that the size of the compiled module stays within maxRatio times the size of the binary
+// plus codeSizeSlack. This is to detect the generated code blowing up, for example, by duplicating the same code.
+//
+// The size is measured as that of the entry written into the file compilation cache, which mostly consists of the
+// machine code.
+func requireCodeSizeWithin(wasmBin []byte, maxRatio int) {
+	dir, err := os.MkdirTemp("", "wazero-fuzz-codesize")
+	if err != nil {
+		panic(err)
+	}
+	defer os.RemoveAll(dir)
+
+	if !compileIntoFileCache(wasmBin, dir) {
+		return
+	}
+
+	var size int64
+	err = filepath.WalkDir(dir, func(path string, d fs.DirEntry, err error) error {
+		if err != nil || d.IsDir() {
+			return err
+		}
+		info, err := d.Info()
+		if err != nil {
+			return err
+		}
+		size += info.Size()
+		return nil
+	})
+	if err != nil {
+		panic(err)
+	}
+
+	if limit := int64(maxRatio*len(wasmBin) + codeSizeSlack); size > limit {
+		panic(fmt.Sprintf("compiled module is too large: %d bytes for %d bytes of binary (ratio=%.1f, limit=%d bytes)",
+			size, len(wasmBin), float64(size)/float64(len(wasmBin)), limit))
+	}
+}
+
+// compileIntoFileCache compiles the binary with the file compilation cache in dir, and returns false if it fails.
+func compileIntoFileCache(wasmBin []byte, dir string) (ok bool) {
+	ctx := context.Background()
+	cache, err := wazero.NewCompilationCacheWithDir(dir)
+	if err != nil {
+		panic(err)
+	}
+	defer func() {
+		if err := cache.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigCompiler().
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads).
+		WithCompilationCache(cache))
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	_, err = r.CompileModule(ctx, wasmBin)
+	return err == nil
+}
@@ -0,0 +1,27 @@
+package main
+
+import (
+	"fmt"
+	"time"
+)
+
+// compileTimeLimit is the time limit of compiling one module used by require_compile_within. This is generous
+// enough for hundreds of functions, so exceeding it indicates the compilation time growing super-linearly.
+const compileTimeLimit = 10 * time.Second
+
+// requireCompileWithin ensures that validation and compilation of the binary finish within the given limit.
+// This is to detect the compilation time growing super-linearly in the number of functions and types.
+func requireCompileWithin(wasmBin []byte, limit time.Duration) {
+	done := make(chan struct{})
+	go func() {
+		defer close(done)
+		tryCompile(wasmBin)
+	}()
+
+	// Wait on the wall-clock so that a compilation which never finishes is caught as well.
+	select {
+	case <-done:
+	case <-time.After(limit):
+		panic(fmt.Sprintf("compilation did not finish within %v", limit))
+	}
+}
@@ -0,0 +1,18 @@
+package main
+
+import (
+	"os"
+	"testing"
+)
+
+// TestReRunFailedCompileWithinCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedCompileWithinCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	requireCompileWithin(wasmBin, compileTimeLimit)
+}
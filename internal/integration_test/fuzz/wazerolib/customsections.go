@@ -0,0 +1,99 @@
+package main
+
+import (
+	"context"
+	"crypto/sha256"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/leb128"
+	"github.com/tetratelabs/wazero/internal/testing/nodiff"
+	"github.com/tetratelabs/wazero/internal/wasm"
+)
+
+// requireCustomSectionsIrrelevant ensures that the given valid binary behaves the same as the one without its custom
+// sections on each engine. As both engines share the decoder, comparing them with each other cannot catch the custom
+// sections changing the behavior of both. The only allowed difference is the function names in the stack traces,
+// which come from the name section, so only the first lines of the errors are compared.
+func requireCustomSectionsIrrelevant(wasmBin []byte) {
+	stripped := withoutCustomSections(wasmBin)
+	for _, config := range []wazero.RuntimeConfig{
+		wazero.NewRuntimeConfigCompiler(),
+		wazero.NewRuntimeConfigInterpreter(),
+	} {
+		withSections, withoutSections := customSectionsTranscript(config, wasmBin), customSectionsTranscript(config, stripped)
+		if withSections != withoutSections {
+			panic(fmt.Sprintf("custom sections changed the behavior\nwith:\n%s\nwithout:\n%s", withSections, withoutSections))
+		}
+	}
+}
+
+// withoutCustomSections returns the copy of the valid binary with all the custom sections removed.
+func withoutCustomSections(wasmBin []byte) []byte {
+	// Keep the magic number and version.
+	ret := append([]byte{}, wasmBin[:8]...)
+	for offset := 8; offset < len(wasmBin); {
+		id := wasmBin[offset]
+		size, sizeLen, err := leb128.LoadUint32(wasmBin[offset+1:])
+		if err != nil {
+			panic(err)
+		}
+		end := offset + 1 + int(sizeLen) + int(size)
+		if id != wasm.SectionIDCustom {
+			ret = append(ret, wasmBin[offset:end]...)
+		}
+		offset = end
+	}
+	return ret
+}
+
+// customSectionsTranscript instantiates the module on a fresh runtime, invokes all the exported functions in order,
+// and returns the transcript of their results and the first lines of the errors, followed by the digest of the memory.
+func customSectionsTranscript(config wazero.RuntimeConfig, wasmBin []byte) string {
+	ctx := context.Background()
+	r := wazero.NewRuntimeWithConfig(ctx, config.
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads))
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	compiled, err := r.CompileModule(ctx, wasmBin)
+	if err != nil {
+		return fmt.Sprintf("compilation error: %s", firstLine(err))
+	}
+	mod, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName(""))
+	if err != nil {
+		return fmt.Sprintf("instantiation error: %s", firstLine(err))
+	}
+
+	defs := compiled.ExportedFunctions()
+	names := make([]string, 0, len(defs))
+	for name := range defs {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	var ret strings.Builder
+	for _, name := range names {
+		res, err := mod.ExportedFunction(name).Call(ctx, nodiff.DummyValues(defs[name].ParamTypes())...)
+		if err != nil {
+			fmt.Fprintf(&ret, "%q: %s\n", name, firstLine(err))
+		} else if !nodiff.HasBasicResults(defs[name]) {
+			// Reference values are not comparable across runtimes.
+			fmt.Fprintf(&ret, "%q: ok\n", name)
+		} else {
+			fmt.Fprintf(&ret, "%q: %v\n", name, res)
+		}
+	}
+	if mem := mod.Memory(); mem != nil {
+		buf, _ := mem.Read(0, mem.Size())
+		fmt.Fprintf(&ret, "memory: %x\n", sha256.Sum256(buf))
+	}
+	return ret.String()
+}
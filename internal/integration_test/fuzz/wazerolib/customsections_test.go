@@ -0,0 +1,17 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/tetratelabs/wazero/internal/testing/require"
+)
+
+func Test_withoutCustomSections(t *testing.T) {
+	header := []byte{0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00}
+	custom := []byte{0x00, 0x03, 0x01, 'a', 0xff}
+	typeSection := []byte{0x01, 0x04, 0x01, 0x60, 0x00, 0x00}
+
+	bin := append(append(append(append([]byte{}, header...), custom...), typeSection...), custom...)
+	require.Equal(t, append(append([]byte{}, header...), typeSection...), withoutCustomSections(bin))
+	require.Equal(t, header, withoutCustomSections(header))
+}
@@ -0,0 +1,198 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"math/rand"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/testing/nodiff"
+	"github.com/tetratelabs/wazero/internal/wasm"
+	"github.com/tetratelabs/wazero/internal/wasm/binary"
+)
+
+// requireDeterministicExports ensures that the exports of the module of all the extern types declared in the binary
+// are exactly those resolvable by their names from the compiled module and its instances. Also, this ensures that
+// invoking each exported function on a fresh instance yields the same result regardless of the order of the
+// invocations, which is shuffled with seed.
+func requireDeterministicExports(wasmBin []byte, seed int64) {
+	for _, config := range []wazero.RuntimeConfig{
+		wazero.NewRuntimeConfigCompiler(),
+		wazero.NewRuntimeConfigInterpreter(),
+	} {
+		requireDeterministicExportsWithConfig(config, wasmBin, seed)
+	}
+}
+
+func requireDeterministicExportsWithConfig(config wazero.RuntimeConfig, wasmBin []byte, seed int64) {
+	const features = api.CoreFeaturesV2 | experimental.CoreFeaturesThreads
+	ctx := context.Background()
+	r := wazero.NewRuntimeWithConfig(ctx, config.WithCoreFeatures(features))
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	compiled, err := r.CompileModule(ctx, wasmBin)
+	if err != nil {
+		return
+	}
+
+	// Decode the binary separately so that the declared exports don't depend on the compiled module.
+	declared, err := binary.DecodeModule(wasmBin, features, wasm.MemoryLimitPages, false, false, false)
+	if err != nil {
+		panic(fmt.Sprintf("compiled but failed to decode: %v", err))
+	}
+	requireCompiledExports(compiled, declared.ExportSection)
+
+	names := make([]string, 0, len(compiled.ExportedFunctions()))
+	for name := range compiled.ExportedFunctions() {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	shuffled := make([]string, len(names))
+	copy(shuffled, names)
+	rand.New(rand.NewSource(seed)).Shuffle(len(shuffled), func(i, j int) {
+		shuffled[i], shuffled[j] = shuffled[j], shuffled[i]
+	})
+
+	sortedResults := invokeEachOnFreshInstance(ctx, r, compiled, declared.ExportSection, names)
+	shuffledResults := invokeEachOnFreshInstance(ctx, r, compiled, declared.ExportSection, shuffled)
+	for _, name := range names {
+		if sortedResults[name] != shuffledResults[name] {
+			panic(fmt.Sprintf("result of '%s' depends on the order of invocations:\n\tsorted: %s\n\tshuffled: %s",
+				name, sortedResults[name], shuffledResults[name]))
+		}
+	}
+}
+
+// exportsSummary returns the summary of the exports for the error messages.
+func exportsSummary(exports []wasm.Export) string {
+	var ret strings.Builder
+	for _, exp := range exports {
+		fmt.Fprintf(&ret, "%q: %s[%d]\n", exp.Name, api.ExternTypeName(exp.Type), exp.Index)
+	}
+	return ret.String()
+}
+
+// requireCompiledExports ensures that the exported functions and memories of compiled are exactly the declared ones.
+func requireCompiledExports(compiled wazero.CompiledModule, declared []wasm.Export) {
+	funcs, mems := compiled.ExportedFunctions(), compiled.ExportedMemories()
+	var funcCount, memCount int
+	for _, exp := range declared {
+		var index uint32
+		var ok bool
+		switch exp.Type {
+		case wasm.ExternTypeFunc:
+			funcCount++
+			var def api.FunctionDefinition
+			if def, ok = funcs[exp.Name]; ok {
+				index = def.Index()
+			}
+		case wasm.ExternTypeMemory:
+			memCount++
+			var def api.MemoryDefinition
+			if def, ok = mems[exp.Name]; ok {
+				index = def.Index()
+			}
+		default:
+			// The compiled module doesn't expose the other extern types.
+			continue
+		}
+		if !ok || index != exp.Index {
+			panic(fmt.Sprintf("compiled module doesn't export %q as %s[%d]", exp.Name, api.ExternTypeName(exp.Type), exp.Index))
+		}
+	}
+	if len(funcs) != funcCount || len(mems) != memCount {
+		panic(fmt.Sprintf("compiled module exports %d functions and %d memories, but %d and %d are declared:\n%s",
+			len(funcs), len(mems), funcCount, memCount, exportsSummary(declared)))
+	}
+}
+
+// requireInstanceExports ensures that the exports which the runtime builds for mod are exactly the declared ones of
+// all the extern types, and that each of them is resolved by its name.
+func requireInstanceExports(mod api.Module, declared []wasm.Export) {
+	inst := mod.(*wasm.ModuleInstance)
+	if len(inst.Exports) != len(declared) {
+		panic(fmt.Sprintf("instance has %d exports, but %d are declared:\n%s", len(inst.Exports), len(declared), exportsSummary(declared)))
+	}
+
+	funcDefs, memDefs := mod.ExportedFunctionDefinitions(), mod.ExportedMemoryDefinitions()
+	var funcCount, memCount int
+	for _, exp := range declared {
+		resolved := inst.Exports[exp.Name]
+		ok := resolved != nil && resolved.Type == exp.Type && resolved.Index == exp.Index
+		switch exp.Type {
+		case wasm.ExternTypeFunc:
+			funcCount++
+			def, hasDef := funcDefs[exp.Name]
+			ok = ok && hasDef && def.Index() == exp.Index && mod.ExportedFunction(exp.Name) != nil
+		case wasm.ExternTypeMemory:
+			memCount++
+			def, hasDef := memDefs[exp.Name]
+			ok = ok && hasDef && def.Index() == exp.Index && mod.ExportedMemory(exp.Name) != nil
+		case wasm.ExternTypeGlobal:
+			ok = ok && mod.ExportedGlobal(exp.Name) != nil
+		}
+		if !ok {
+			panic(fmt.Sprintf("instance doesn't resolve %q as %s[%d]", exp.Name, api.ExternTypeName(exp.Type), exp.Index))
+		}
+	}
+	if len(funcDefs) != funcCount || len(memDefs) != memCount {
+		panic(fmt.Sprintf("instance exports %d functions and %d memories, but %d and %d are declared:\n%s",
+			len(funcDefs), len(memDefs), funcCount, memCount, exportsSummary(declared)))
+	}
+}
+
+// invokeEachOnFreshInstance invokes each exported function in the given order, each on the fresh instance of compiled,
+// and returns the formatted results keyed on the export name.
+func invokeEachOnFreshInstance(ctx context.Context, r wazero.Runtime, compiled wazero.CompiledModule, declared []wasm.Export, names []string) map[string]string {
+	ret := make(map[string]string, len(names))
+	for _, name := range names {
+		ret[name] = invokeOnFreshInstance(ctx, r, compiled, declared, name)
+	}
+	return ret
+}
+
+// invokeOnFreshInstance instantiates compiled, checks its exports against the declared ones, invokes the exported
+// function with the dummy parameters, and returns the formatted result or error.
+func invokeOnFreshInstance(ctx context.Context, r wazero.Runtime, compiled wazero.CompiledModule, declared []wasm.Export, name string) string {
+	mod, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName(""))
+	if err != nil {
+		return fmt.Sprintf("instantiation error: %s", firstLine(err))
+	}
+	defer func() {
+		if err := mod.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	requireInstanceExports(mod, declared)
+	return callWithDummyParams(ctx, mod.ExportedFunction(name))
+}
+
+// callWithDummyParams invokes f with the dummy parameters, and returns the formatted result or error.
+func callWithDummyParams(ctx context.Context, f api.Function) string {
+	def := f.Definition()
+	if !nodiff.HasBasicResults(def) {
+		// Reference values are not comparable across instances.
+		return "skipped"
+	}
+
+	res, err := f.Call(ctx, nodiff.DummyValues(def.ParamTypes())...)
+	if err != nil {
+		return fmt.Sprintf("error: %s", firstLine(err))
+	}
+	return fmt.Sprintf("%v", res)
+}
+
+// firstLine returns the first line of the error message, which excludes the stack trace.
+func firstLine(err error) string {
+	msg, _, _ := strings.Cut(err.Error(), "\n")
+	return msg
+}
@@ -0,0 +1,21 @@
+package main
+
+import (
+	"os"
+	"testing"
+)
+
+// TestReRunFailedDeterministicExportsCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedDeterministicExportsCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	// Try several orders of invocations as the seed used by the fuzzer is not recorded.
+	for seed := int64(0); seed < 10; seed++ {
+		requireDeterministicExports(wasmBin, seed)
+	}
+}
@@ -4,6 +4,7 @@ import "C"
 import (
 	"context"
 	"math"
+	"os"
 	"reflect"
 	"strings"
 	"unsafe"
@@ -21,7 +22,7 @@ func main() {}
 // And if there's diff, this also saves the problematic binary and wat into testdata directory.
 //
 //export require_no_diff
-func require_no_diff(binaryPtr uintptr, binarySize int, checkMemory bool, checkLogging bool) {
+func require_no_diff(binaryPtr uintptr, binarySize int, checkMemory bool, checkLogging bool, checkTrapReason bool) {
 	var wasmBin []byte
 	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
 	wasmHdr.Data = binaryPtr
@@ -36,7 +37,7 @@ func require_no_diff(binaryPtr uintptr, binarySize int, checkMemory bool, checkL
 		}
 	}()
 
-	nodiff.RequireNoDiff(wasmBin, checkMemory, checkLogging, func(err error) {
+	nodiff.RequireNoDiff(wasmBin, checkMemory, checkLogging, checkTrapReason, func(err error) {
 		if err != nil {
 			panic(err)
 		}
@@ -45,6 +46,159 @@ func require_no_diff(binaryPtr uintptr, binarySize int, checkMemory bool, checkL
 	failed = false
 }
 
+// require_no_diff_after_trap is the same as require_no_diff with checkMemory and checkTrapReason except that the
+// memory is also compared right after each invocation which traps in both engines.
+//
+//export require_no_diff_after_trap
+func require_no_diff_after_trap(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedRequireNoDiffAfterTrapCase")
+		}
+	}()
+
+	nodiff.RequireNoDiffAfterTrap(wasmBin, func(err error) {
+		if err != nil {
+			panic(err)
+		}
+	})
+
+	failed = false
+}
+
+// require_no_diff_reentrant is the same as require_no_diff except that the functions imported from the "host" module
+// re-enter the guest up to reentrancyMaxDepth nested calls.
+//
+//export require_no_diff_reentrant
+func require_no_diff_reentrant(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedRequireNoDiffReentrantCase")
+		}
+	}()
+
+	nodiff.RequireNoDiffReentrant(wasmBin, reentrancyMaxDepth, func(err error) {
+		if err != nil {
+			panic(err)
+		}
+	})
+
+	failed = false
+}
+
+// require_no_diff_with_args is the same as require_no_diff except that the exported functions are also invoked
+// with the arguments decoded from the given args. If there's diff, the args are saved together with the binary
+// as a custom section.
+//
+//export require_no_diff_with_args
+func require_no_diff_with_args(binaryPtr uintptr, binarySize int, argsPtr uintptr, argsSize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	var args []byte
+	argsHdr := (*reflect.SliceHeader)(unsafe.Pointer(&args))
+	argsHdr.Data = argsPtr
+	argsHdr.Len = argsSize
+	argsHdr.Cap = argsSize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(withArgsSection(wasmBin, args), "TestReRunFailedRequireNoDiffWithArgsCase")
+		}
+	}()
+
+	nodiff.RequireNoDiffWithArgs(wasmBin, args, false, func(err error) {
+		if err != nil {
+			panic(err)
+		}
+	})
+
+	failed = false
+}
+
+// require_no_diff_deep_reentrant is the same as require_no_diff_reentrant except that the nested calls re-entering
+// the guest go up to deepReentrancyMaxDepth.
+//
+//export require_no_diff_deep_reentrant
+func require_no_diff_deep_reentrant(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedRequireNoDiffDeepReentrantCase")
+		}
+	}()
+
+	nodiff.RequireNoDiffReentrant(wasmBin, deepReentrancyMaxDepth, func(err error) {
+		if err != nil {
+			panic(err)
+		}
+	})
+
+	failed = false
+}
+
+// require_no_diff_custom_sections ensures that the compiler and the interpreter consistently accept or reject the given
+// binary with possibly malformed custom sections. If it is accepted, this also ensures that the behavior is the same
+// between them, as well as the same as that of the binary without the custom sections on each of them.
+// And if there's diff, this also saves the problematic binary and wat into testdata directory.
+//
+//export require_no_diff_custom_sections
+func require_no_diff_custom_sections(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedCustomSectionsCase")
+		}
+	}()
+
+	if requireSameCompilationError(wasmBin) {
+		// Compare the trap reasons as well since they contain the function names from the name section.
+		nodiff.RequireNoDiff(wasmBin, false, false, true, func(err error) {
+			if err != nil {
+				panic(err)
+			}
+		})
+		requireCustomSectionsIrrelevant(wasmBin)
+	}
+
+	failed = false
+}
+
 // validate accepts maybe-invalid Wasm module bytes and ensures that our validation phase works correctly
 // as well as the compiler doesn't panic during compilation!
 //
@@ -68,6 +222,246 @@ func validate(binaryPtr uintptr, binarySize int) {
 	failed = false
 }
 
+// require_float_oracle ensures that the floating point results for the given binary are identical to the checked-in
+// oracle recorded on possibly another architecture. The oracles are only recorded when floatOracleRecordEnv is set.
+// And if there's diff, this also saves the problematic binary into testdata directory.
+//
+//export require_float_oracle
+func require_float_oracle(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedFloatOracleCase")
+		}
+	}()
+
+	requireFloatOracle(wasmBin, floatOracleDir, os.Getenv(floatOracleRecordEnv) != "")
+	failed = false
+}
+
+// require_compile_within ensures that the compilation of the given binary finishes within compileTimeLimit.
+// And if it doesn't, this also saves the problematic binary into testdata directory.
+//
+//export require_compile_within
+func require_compile_within(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedCompileWithinCase")
+		}
+	}()
+
+	requireCompileWithin(wasmBin, compileTimeLimit)
+	failed = false
+}
+
+// require_code_size_within ensures that the compiled code of the given binary is at most codeSizeMaxRatio times as
+// large as it.
+// And if it isn't, this also saves the problematic binary into testdata directory.
+//
+//export require_code_size_within
+func require_code_size_within(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedCodeSizeWithinCase")
+		}
+	}()
+
+	requireCodeSizeWithin(wasmBin, codeSizeMaxRatio)
+	failed = false
+}
+
+// cache_key derives the key which identifies the compiled module for the given binary in the file cache, and writes it
+// into the 32-byte buffer pointed by keyPtr. This returns the number of the functions to which the listeners are
+// attached, or -1 if the binary cannot be decoded.
+//
+//export cache_key
+func cache_key(binaryPtr uintptr, binarySize int, withListeners, ensureTermination bool, keyPtr uintptr) int {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	k, listenerCount, ok := cacheKey(wasmBin, withListeners, ensureTermination)
+	if !ok {
+		return -1
+	}
+
+	var key []byte
+	keyHdr := (*reflect.SliceHeader)(unsafe.Pointer(&key))
+	keyHdr.Data = keyPtr
+	keyHdr.Len = len(k)
+	keyHdr.Cap = len(k)
+	copy(key, k[:])
+	return listenerCount
+}
+
+// require_same_validation ensures that the compiler and the interpreter agree on whether the given binary is valid,
+// and that neither engine panics on executing it if valid.
+// And if not, this also saves the problematic binary into testdata directory.
+//
+//export require_same_validation
+func require_same_validation(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedSameValidationCase")
+		}
+	}()
+
+	requireSameValidation(wasmBin)
+	failed = false
+}
+
+// require_deterministic_exports ensures that the exports of the given binary are enumerated deterministically, and that
+// the result of each exported function doesn't depend on the order of invocations shuffled with seed.
+// And if it does, this also saves the problematic binary into testdata directory.
+//
+//export require_deterministic_exports
+func require_deterministic_exports(binaryPtr uintptr, binarySize int, seed int64) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedDeterministicExportsCase")
+		}
+	}()
+
+	requireDeterministicExports(wasmBin, seed)
+	failed = false
+}
+
+// require_no_diff_linked_globals ensures that the globals exported by the given binary are observed in the same way
+// between the compiler and the interpreter when they are imported by another module.
+// And if there's diff, this also saves the problematic binary into testdata directory.
+//
+//export require_no_diff_linked_globals
+func require_no_diff_linked_globals(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedLinkedGlobalsCase")
+		}
+	}()
+
+	requireNoDiffLinkedGlobals(wasmBin)
+	failed = false
+}
+
+// require_isolated_instances ensures that the given number of instances of the same compiled module don't share
+// their state with each other. And if they do, this also saves the problematic binary into testdata directory
+// together with the number of the instances as a custom section.
+//
+//export require_isolated_instances
+func require_isolated_instances(binaryPtr uintptr, binarySize int, instances int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(withInstancesSection(wasmBin, instances), "TestReRunFailedIsolatedInstancesCase")
+		}
+	}()
+
+	requireIsolatedInstances(wasmBin, instances)
+	failed = false
+}
+
+// require_same_traps_after_reload ensures that the given binary behaves the same after being reloaded from the file
+// compilation cache, including the trap reasons.
+// And if not, this also saves the problematic binary into testdata directory.
+//
+//export require_same_traps_after_reload
+func require_same_traps_after_reload(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedTrapsAfterReloadCase")
+		}
+	}()
+
+	requireSameTrapsAfterReload(wasmBin)
+	failed = false
+}
+
+// require_isolated_runtimes ensures that two runtimes don't share their state with each other while running the
+// given binary. And if they do, this also saves the problematic binary into testdata directory.
+//
+//export require_isolated_runtimes
+func require_isolated_runtimes(binaryPtr uintptr, binarySize int) {
+	var wasmBin []byte
+	wasmHdr := (*reflect.SliceHeader)(unsafe.Pointer(&wasmBin))
+	wasmHdr.Data = binaryPtr
+	wasmHdr.Len = binarySize
+	wasmHdr.Cap = binarySize
+
+	failed := true
+	defer func() {
+		if failed {
+			// If the test fails, we save the binary and wat into testdata directory.
+			saveFailedBinary(wasmBin, "TestReRunFailedIsolatedRuntimesCase")
+		}
+	}()
+
+	requireIsolatedRuntimes(wasmBin)
+	failed = false
+}
+
 //export test_signal_stack
 func test_signal_stack() {
 	// (module
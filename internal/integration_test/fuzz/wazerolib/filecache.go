@@ -0,0 +1,86 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/testing/nodiff"
+)
+
+// requireSameTrapsAfterReload ensures that the module loaded from the file compilation cache behaves the same as the
+// one compiled from scratch, including the trap reasons and the stack traces of the failed invocations.
+func requireSameTrapsAfterReload(wasmBin []byte) {
+	dir, err := os.MkdirTemp("", "wazero-fuzz-filecache")
+	if err != nil {
+		panic(err)
+	}
+	defer os.RemoveAll(dir)
+
+	// The first run compiles the module and writes it into dir, and the second one loads it from there.
+	compiled := fileCachedTranscript(wasmBin, dir)
+	reloaded := fileCachedTranscript(wasmBin, dir)
+	if compiled != reloaded {
+		panic(fmt.Sprintf("behavior changed after reloading from the file cache\ncompiled:\n%s\nreloaded:\n%s", compiled, reloaded))
+	}
+}
+
+// fileCachedTranscript instantiates the module on a fresh runtime with the file compilation cache in dir, invokes
+// all the exported functions in order, and returns the transcript of their results and errors.
+func fileCachedTranscript(wasmBin []byte, dir string) string {
+	ctx := context.Background()
+	cache, err := wazero.NewCompilationCacheWithDir(dir)
+	if err != nil {
+		panic(err)
+	}
+	defer func() {
+		if err := cache.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigCompiler().
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads).
+		WithCompilationCache(cache))
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	compiled, err := r.CompileModule(ctx, wasmBin)
+	if err != nil {
+		return fmt.Sprintf("compilation error: %v", err)
+	}
+	mod, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName(""))
+	if err != nil {
+		return fmt.Sprintf("instantiation error: %v", err)
+	}
+
+	defs := compiled.ExportedFunctions()
+	names := make([]string, 0, len(defs))
+	for name := range defs {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	var ret strings.Builder
+	for _, name := range names {
+		res, err := mod.ExportedFunction(name).Call(ctx, nodiff.DummyValues(defs[name].ParamTypes())...)
+		if err != nil {
+			// The full error message is compared so that the stack traces are also checked.
+			fmt.Fprintf(&ret, "%q: %v\n", name, err)
+		} else if !nodiff.HasBasicResults(defs[name]) {
+			// Reference values are not comparable across runtimes.
+			fmt.Fprintf(&ret, "%q: ok\n", name)
+		} else {
+			fmt.Fprintf(&ret, "%q: %v\n", name, res)
+		}
+	}
+	return ret.String()
+}
@@ -0,0 +1,155 @@
+package main
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"os"
+	"path"
+	"runtime"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/testing/nodiff"
+	"github.com/tetratelabs/wazero/internal/wasm"
+)
+
+// floatOracleDir is the directory where the corpus of requireFloatOracle is checked in. For each binary, the directory
+// contains the binary itself as {checksum}.wasm and its oracle as {checksum}.txt, so that the results produced on one
+// architecture can be replayed on another by TestFloatOracle.
+const floatOracleDir = "wazerolib/float_oracle"
+
+// floatOracleRecordEnv is the environment variable which enables recording the new oracles into the corpus.
+// Recording is opt-in as otherwise every fuzz input would be written into the source tree.
+const floatOracleRecordEnv = "WAZERO_FUZZ_RECORD_FLOAT_ORACLE"
+
+// floatOraclePath returns the path to the oracle file for the given binary, which is keyed by its checksum.
+func floatOraclePath(oracleDir string, wasmBin []byte) string {
+	return path.Join(oracleDir, floatOracleChecksum(wasmBin)+".txt")
+}
+
+// floatOracleChecksum returns the checksum of the binary which the files in the corpus are named after.
+func floatOracleChecksum(wasmBin []byte) string {
+	checksum := sha256.Sum256(wasmBin)
+	return hex.EncodeToString(checksum[:])
+}
+
+// requireFloatOracle ensures that the floating point results of the exported functions are identical to the ones
+// recorded in the checked-in oracle, which is possibly produced on another architecture. If the oracle doesn't exist,
+// this does nothing unless record is true, in which case the binary and the results on this host are added to the
+// corpus as the new oracle.
+func requireFloatOracle(wasmBin []byte, oracleDir string, record bool) {
+	oraclePath := floatOraclePath(oracleDir, wasmBin)
+	oracle, err := os.ReadFile(oraclePath)
+	if errors.Is(err, os.ErrNotExist) {
+		if record {
+			recordFloatOracle(wasmBin, oracleDir)
+		}
+		return
+	} else if err != nil {
+		panic(err)
+	}
+
+	results, ok := floatResults(wasmBin)
+	if !ok {
+		panic(fmt.Sprintf("%s cannot be instantiated", oraclePath))
+	}
+
+	// The first line is the label of where the oracle is produced, which is GOARCH for the recorded ones.
+	oracleArch, oracleResults, _ := strings.Cut(string(oracle), "\n")
+	if oracleResults != results {
+		panic(fmt.Sprintf("floating point results mismatch against %s\n%s:\n%s\n%s:\n%s",
+			oraclePath, oracleArch, oracleResults, runtime.GOARCH, results))
+	}
+}
+
+// recordFloatOracle writes the binary and its floating point results on this host into the corpus in oracleDir.
+func recordFloatOracle(wasmBin []byte, oracleDir string) {
+	results, ok := floatResults(wasmBin)
+	if !ok || results == "" {
+		// Nothing to compare across architectures.
+		return
+	}
+
+	if err := os.MkdirAll(oracleDir, 0o755); err != nil {
+		panic(err)
+	}
+	checksum := floatOracleChecksum(wasmBin)
+	if err := os.WriteFile(path.Join(oracleDir, checksum+".wasm"), wasmBin, 0o644); err != nil {
+		panic(err)
+	}
+	if err := os.WriteFile(path.Join(oracleDir, checksum+".txt"), []byte(runtime.GOARCH+"\n"+results), 0o644); err != nil {
+		panic(err)
+	}
+}
+
+// floatResults invokes the exported functions which return floating point values with the compiler, and returns
+// the formatted results in the deterministic order. ok is false if the module cannot be instantiated.
+func floatResults(wasmBin []byte) (results string, ok bool) {
+	ctx := context.Background()
+	r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigCompiler().
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads))
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	mod, err := r.Instantiate(ctx, wasmBin)
+	if err != nil {
+		return "", false
+	}
+
+	defs := mod.ExportedFunctionDefinitions()
+	names := make([]string, 0, len(defs))
+	for name := range defs {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	var ret strings.Builder
+	for _, name := range names {
+		def := defs[name]
+		if !nodiff.HasBasicResults(def) {
+			// Reference types are not comparable across runtimes.
+			continue
+		}
+
+		var hasFloat bool
+		for _, rt := range def.ResultTypes() {
+			hasFloat = hasFloat || rt == api.ValueTypeF32 || rt == api.ValueTypeF64 || rt == wasm.ValueTypeV128
+		}
+		if !hasFloat {
+			continue
+		}
+
+		res, err := mod.ExportedFunction(name).Call(ctx, nodiff.DummyValues(def.ParamTypes())...)
+		if err != nil {
+			// Only the first line is recorded as the rest is the stack trace, which is irrelevant here.
+			fmt.Fprintf(&ret, "%q: %s\n", name, firstLine(err))
+			continue
+		}
+
+		var i int
+		fmt.Fprintf(&ret, "%q:", name)
+		for _, rt := range def.ResultTypes() {
+			switch rt {
+			case api.ValueTypeI32, api.ValueTypeF32:
+				fmt.Fprintf(&ret, " %#x", uint32(res[i]))
+			case api.ValueTypeI64, api.ValueTypeF64:
+				fmt.Fprintf(&ret, " %#x", res[i])
+			case wasm.ValueTypeV128:
+				fmt.Fprintf(&ret, " (%#x,%#x)", res[i], res[i+1])
+				i++ // We need to advance twice (lower and higher 64bits)
+			}
+			i++
+		}
+		ret.WriteByte('\n')
+	}
+	return ret.String(), true
+}
@@ -0,0 +1,45 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/tetratelabs/wazero/internal/testing/require"
+)
+
+// TestFloatOracle replays the checked-in corpus of the float_oracle target against the oracles, which are possibly
+// recorded on another architecture.
+func TestFloatOracle(t *testing.T) {
+	const oracleDir = "float_oracle"
+	binaryPaths, err := filepath.Glob(filepath.Join(oracleDir, "*.wasm"))
+	require.NoError(t, err)
+	require.NotEqual(t, 0, len(binaryPaths))
+
+	for _, binaryPath := range binaryPaths {
+		t.Run(filepath.Base(binaryPath), func(t *testing.T) {
+			wasmBin, err := os.ReadFile(binaryPath)
+			require.NoError(t, err)
+			// The binary must be named after its checksum so that the oracle is looked up.
+			require.Equal(t, floatOracleChecksum(wasmBin)+".wasm", filepath.Base(binaryPath))
+			_, err = os.Stat(strings.TrimSuffix(binaryPath, ".wasm") + ".txt")
+			require.NoError(t, err)
+
+			requireFloatOracle(wasmBin, oracleDir, false)
+		})
+	}
+}
+
+// TestReRunFailedFloatOracleCase re-runs the failed case specified by WASM_BINARY_PATH against its oracle.
+func TestReRunFailedFloatOracleCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	// Only compares against the existing oracle, and never records a new one.
+	requireFloatOracle(wasmBin, "float_oracle", false)
+}
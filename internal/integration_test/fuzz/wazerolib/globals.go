@@ -0,0 +1,194 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/leb128"
+	"github.com/tetratelabs/wazero/internal/testing/binaryencoding"
+	"github.com/tetratelabs/wazero/internal/testing/nodiff"
+	"github.com/tetratelabs/wazero/internal/wasm"
+	"github.com/tetratelabs/wazero/internal/wasm/binary"
+)
+
+// exporterModuleName is the name of the given module, whose exported globals are imported by the observer module.
+const exporterModuleName = "exporter"
+
+// linkedGlobal is a global exported by the exporter module and imported by the observer module.
+type linkedGlobal struct {
+	name string
+	tp   wasm.GlobalType
+}
+
+// requireNoDiffLinkedGlobals ensures that the compiler and the interpreter observe the same values of the globals
+// exported by the given module through another module importing them, while the globals are mutated from both sides.
+func requireNoDiffLinkedGlobals(wasmBin []byte) {
+	const features = api.CoreFeaturesV2 | experimental.CoreFeaturesThreads
+	m, err := binary.DecodeModule(wasmBin, features, wasm.MemoryLimitPages, false, false, false)
+	if err != nil {
+		return
+	}
+	observerBin, globals := linkedGlobalsObserver(m)
+	if len(globals) == 0 {
+		return
+	}
+
+	compilerObserved, compilerStackOverflow := observeLinkedGlobals(
+		wazero.NewRuntimeConfigCompiler().WithCoreFeatures(features), wasmBin, observerBin, globals)
+	interpreterObserved, interpreterStackOverflow := observeLinkedGlobals(
+		wazero.NewRuntimeConfigInterpreter().WithCoreFeatures(features), wasmBin, observerBin, globals)
+	if compilerStackOverflow || interpreterStackOverflow {
+		// The depth at which stack overflow happens differs between engines, so the mutations are not comparable.
+		return
+	}
+
+	if compilerObserved != interpreterObserved {
+		panic(fmt.Sprintf("linked globals mismatch\ninterpreter:\n%s\ncompiler:\n%s", interpreterObserved, compilerObserved))
+	}
+}
+
+// linkedGlobalsObserver returns the binary of the observer module which imports the scalar globals defined and exported
+// by m. The observer module exports "get{i}" and, if the global is mutable, "set{i}" functions for the i-th global.
+func linkedGlobalsObserver(m *wasm.Module) (bin []byte, globals []linkedGlobal) {
+	observer := &wasm.Module{}
+	addFunction := func(name string, tp wasm.FunctionType, body []byte) {
+		observer.TypeSection = append(observer.TypeSection, tp)
+		observer.FunctionSection = append(observer.FunctionSection, wasm.Index(len(observer.TypeSection)-1))
+		observer.CodeSection = append(observer.CodeSection, wasm.Code{Body: body})
+		observer.ExportSection = append(observer.ExportSection, wasm.Export{
+			Type: wasm.ExternTypeFunc, Name: name, Index: wasm.Index(len(observer.FunctionSection) - 1),
+		})
+	}
+
+	for _, exp := range m.ExportSection {
+		if exp.Type != wasm.ExternTypeGlobal || exp.Index < m.ImportGlobalCount {
+			// Only the globals defined in m are linked for simplicity.
+			continue
+		}
+		tp := m.GlobalSection[exp.Index-m.ImportGlobalCount].Type
+		switch tp.ValType {
+		case wasm.ValueTypeI32, wasm.ValueTypeI64, wasm.ValueTypeF32, wasm.ValueTypeF64:
+		default:
+			continue
+		}
+
+		i := len(globals)
+		globals = append(globals, linkedGlobal{name: exp.Name, tp: tp})
+		observer.ImportSection = append(observer.ImportSection, wasm.Import{
+			Type: wasm.ExternTypeGlobal, Module: exporterModuleName, Name: exp.Name, DescGlobal: tp,
+		})
+
+		index := leb128.EncodeUint32(uint32(i))
+		addFunction(fmt.Sprintf("get%d", i),
+			wasm.FunctionType{Results: []wasm.ValueType{tp.ValType}},
+			append(append([]byte{wasm.OpcodeGlobalGet}, index...), wasm.OpcodeEnd))
+		if tp.Mutable {
+			addFunction(fmt.Sprintf("set%d", i),
+				wasm.FunctionType{Params: []wasm.ValueType{tp.ValType}},
+				append(append([]byte{wasm.OpcodeLocalGet, 0, wasm.OpcodeGlobalSet}, index...), wasm.OpcodeEnd))
+		}
+	}
+	return binaryencoding.EncodeModule(observer), globals
+}
+
+// observeLinkedGlobals instantiates the exporter and observer modules, mutates the linked globals via both of them,
+// and returns the transcript of the invocations and the observed values. stackOverflow is true if any invocation
+// results in stack overflow.
+func observeLinkedGlobals(config wazero.RuntimeConfig, wasmBin, observerBin []byte, globals []linkedGlobal) (transcript string, stackOverflow bool) {
+	ctx := context.Background()
+	r := wazero.NewRuntimeWithConfig(ctx, config)
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	var ret strings.Builder
+	exporter, err := r.InstantiateWithConfig(ctx, wasmBin, wazero.NewModuleConfig().WithName(exporterModuleName))
+	if err != nil {
+		fmt.Fprintf(&ret, "instantiation error: %s\n", firstLine(err))
+		return ret.String(), strings.Contains(err.Error(), "stack overflow")
+	}
+	observer, err := r.InstantiateWithConfig(ctx, observerBin, wazero.NewModuleConfig().WithName("observer"))
+	if err != nil {
+		panic(err)
+	}
+
+	// observe records the values of the globals seen from both the observer module and the exporter module.
+	observe := func(event string) {
+		fmt.Fprintf(&ret, "%s:", event)
+		for i, g := range globals {
+			res, err := observer.ExportedFunction(fmt.Sprintf("get%d", i)).Call(ctx)
+			if err != nil {
+				panic(err)
+			}
+			fmt.Fprintf(&ret, " %s=%s/%s", g.name, formatValue(g.tp.ValType, res[0]),
+				formatValue(g.tp.ValType, exporter.ExportedGlobal(g.name).Get()))
+		}
+		ret.WriteByte('\n')
+	}
+
+	defs := exporter.ExportedFunctionDefinitions()
+	names := make([]string, 0, len(defs))
+	for name := range defs {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	// callExports invokes all the exported functions of the exporter module which might mutate the globals.
+	callExports := func() {
+		for _, name := range names {
+			if _, err := exporter.ExportedFunction(name).Call(ctx, nodiff.DummyValues(defs[name].ParamTypes())...); err != nil {
+				stackOverflow = stackOverflow || strings.Contains(err.Error(), "stack overflow")
+				fmt.Fprintf(&ret, "%q: %s\n", name, firstLine(err))
+			}
+			observe(fmt.Sprintf("after %q", name))
+		}
+	}
+
+	observe("initial")
+	// Mutate the globals via the exporter module.
+	callExports()
+	// Mutate the globals via the observer module, and let the exporter module see the new values.
+	for i, g := range globals {
+		if !g.tp.Mutable {
+			continue
+		}
+		if _, err = observer.ExportedFunction(fmt.Sprintf("set%d", i)).Call(ctx, setValue(g.tp.ValType)); err != nil {
+			panic(err)
+		}
+		observe(fmt.Sprintf("after set%d", i))
+	}
+	callExports()
+	return ret.String(), stackOverflow
+}
+
+// setValue returns the value set to the global of the given scalar type via the observer module, encoded as required
+// by api.Function.Call.
+func setValue(vt wasm.ValueType) uint64 {
+	switch vt {
+	case wasm.ValueTypeI32:
+		return api.EncodeU32(0x89abcdef)
+	case wasm.ValueTypeF32:
+		return api.EncodeF32(-1.5)
+	case wasm.ValueTypeF64:
+		return api.EncodeF64(-1.5)
+	default: // wasm.ValueTypeI64
+		return 0x0123456789abcdef
+	}
+}
+
+// formatValue formats the value of the given scalar type.
+func formatValue(vt wasm.ValueType, v uint64) string {
+	switch vt {
+	case wasm.ValueTypeI32, wasm.ValueTypeF32:
+		return fmt.Sprintf("%#x", uint32(v))
+	default:
+		return fmt.Sprintf("%#x", v)
+	}
+}
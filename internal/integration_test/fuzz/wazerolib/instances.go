@@ -0,0 +1,137 @@
+package main
+
+import (
+	"context"
+	"crypto/sha256"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/wasm"
+)
+
+// requireIsolatedInstances ensures that the instances of the same compiled module don't share their state.
+// The i-th of the given number of instances, all of which are alive at the same time, is mutated by invoking all the
+// exported functions i times in total, interleaved with the other instances. Then its state must be the same as the one
+// of a fresh instance mutated alone in the same way.
+//
+// The states are also compared between the compiler and the interpreter.
+func requireIsolatedInstances(wasmBin []byte, instances int) {
+	compilerStates, compilerStackOverflow := isolatedInstanceStates(wazero.NewRuntimeConfigCompiler(), wasmBin, instances)
+	interpreterStates, interpreterStackOverflow := isolatedInstanceStates(wazero.NewRuntimeConfigInterpreter(), wasmBin, instances)
+	if compilerStackOverflow || interpreterStackOverflow {
+		// The depth at which stack overflow happens differs between engines, so the states are not comparable.
+		return
+	}
+
+	if len(compilerStates) != len(interpreterStates) {
+		panic(fmt.Sprintf("instance states mismatch:\n\tinterpreter: %v\n\tcompiler: %v", interpreterStates, compilerStates))
+	}
+	for i := range compilerStates {
+		if compilerStates[i] != interpreterStates[i] {
+			panic(fmt.Sprintf("state of instance %d mismatch\ninterpreter:\n%s\ncompiler:\n%s", i, interpreterStates[i], compilerStates[i]))
+		}
+	}
+}
+
+// isolatedInstanceStates implements requireIsolatedInstances for the given config, and returns the states of the
+// instances. stackOverflow is true if any invocation results in stack overflow.
+func isolatedInstanceStates(config wazero.RuntimeConfig, wasmBin []byte, instances int) (states []string, stackOverflow bool) {
+	ctx := context.Background()
+	r := wazero.NewRuntimeWithConfig(ctx, config.
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads))
+	defer func() {
+		if err := r.Close(ctx); err != nil {
+			panic(err)
+		}
+	}()
+
+	compiled, err := r.CompileModule(ctx, wasmBin)
+	if err != nil {
+		return []string{fmt.Sprintf("compilation error: %s", firstLine(err))}, false
+	}
+
+	names := make([]string, 0, len(compiled.ExportedFunctions()))
+	for name := range compiled.ExportedFunctions() {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	instantiate := func() api.Module {
+		mod, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName(""))
+		if err != nil {
+			// The instantiation has already succeeded once, so the subsequent ones must succeed as well.
+			panic(err)
+		}
+		return mod
+	}
+
+	// invokeAll invokes all the exported functions of mod in order, and writes their results into transcript.
+	invokeAll := func(mod api.Module, transcript *strings.Builder) {
+		for _, name := range names {
+			res := callWithDummyParams(ctx, mod.ExportedFunction(name))
+			stackOverflow = stackOverflow || strings.Contains(res, "stack overflow")
+			fmt.Fprintf(transcript, "%q: %s\n", name, res)
+		}
+	}
+
+	first, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName(""))
+	if err != nil {
+		return []string{fmt.Sprintf("instantiation error: %s", firstLine(err))}, strings.Contains(err.Error(), "stack overflow")
+	}
+	mods := []api.Module{first}
+	for len(mods) < instances {
+		mods = append(mods, instantiate())
+	}
+
+	transcripts := make([]strings.Builder, len(mods))
+	for round := 1; round < len(mods); round++ {
+		for i := round; i < len(mods); i++ {
+			invokeAll(mods[i], &transcripts[i])
+		}
+	}
+
+	for i, mod := range mods {
+		state := transcripts[i].String() + instanceState(mod)
+
+		alone := instantiate()
+		var transcript strings.Builder
+		for round := 0; round < i; round++ {
+			invokeAll(alone, &transcript)
+		}
+		if expected := transcript.String() + instanceState(alone); state != expected {
+			panic(fmt.Sprintf("instance %d is not isolated:\n%s\nvs mutated alone:\n%s", i, state, expected))
+		}
+		if err = alone.Close(ctx); err != nil {
+			panic(err)
+		}
+		states = append(states, state)
+	}
+	return
+}
+
+// instanceState returns the summary of the memory and the globals of mod. Reference values are excluded
+// as they are not comparable across instances.
+func instanceState(mod api.Module) string {
+	inst := mod.(*wasm.ModuleInstance)
+
+	var ret strings.Builder
+	if mem := inst.MemoryInstance; mem != nil {
+		fmt.Fprintf(&ret, "memory: len=%d sha256=%x\n", len(mem.Buffer), sha256.Sum256(mem.Buffer))
+	}
+	for i, g := range inst.Globals {
+		lo, hi := g.Value()
+		switch g.Type.ValType {
+		case wasm.ValueTypeI32, wasm.ValueTypeF32:
+			fmt.Fprintf(&ret, "global[%d]: %#x\n", i, uint32(lo))
+		case wasm.ValueTypeI64, wasm.ValueTypeF64:
+			fmt.Fprintf(&ret, "global[%d]: %#x\n", i, lo)
+		case wasm.ValueTypeV128:
+			fmt.Fprintf(&ret, "global[%d]: %#x %#x\n", i, lo, hi)
+		}
+	}
+	return ret.String()
+}
@@ -0,0 +1,23 @@
+package main
+
+import (
+	"os"
+	"testing"
+)
+
+// TestReRunFailedIsolatedInstancesCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedIsolatedInstancesCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	instances, ok := instancesFromSection(wasmBin)
+	if !ok {
+		// The binary isn't saved by the fuzz target, so use the smallest number of the instances it chooses.
+		instances = 2
+	}
+	requireIsolatedInstances(wasmBin, instances)
+}
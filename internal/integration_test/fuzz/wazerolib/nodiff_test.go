@@ -5,6 +5,7 @@ import (
 	"testing"
 
 	"github.com/tetratelabs/wazero/internal/testing/nodiff"
+	"github.com/tetratelabs/wazero/internal/testing/require"
 )
 
 // TestReRunFailedRequireNoDiffCase re-runs the failed case specified by WASM_BINARY_NAME in testdata directory.
@@ -16,5 +17,68 @@ func TestReRunFailedRequireNoDiffCase(t *testing.T) {
 		t.Skip(err)
 	}
 
-	nodiff.RequireNoDiffT(t, wasmBin, true, true)
+	nodiff.RequireNoDiffT(t, wasmBin, true, true, true)
+}
+
+// TestReRunFailedRequireNoDiffAfterTrapCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedRequireNoDiffAfterTrapCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	nodiff.RequireNoDiffAfterTrap(wasmBin, func(err error) { require.NoError(t, err) })
+}
+
+// TestReRunFailedRequireNoDiffReentrantCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedRequireNoDiffReentrantCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	nodiff.RequireNoDiffReentrant(wasmBin, reentrancyMaxDepth, func(err error) { require.NoError(t, err) })
+}
+
+// TestReRunFailedRequireNoDiffDeepReentrantCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedRequireNoDiffDeepReentrantCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	nodiff.RequireNoDiffReentrant(wasmBin, deepReentrancyMaxDepth, func(err error) { require.NoError(t, err) })
+}
+
+// TestReRunFailedCustomSectionsCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedCustomSectionsCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	if requireSameCompilationError(wasmBin) {
+		nodiff.RequireNoDiffT(t, wasmBin, false, false, true)
+		requireCustomSectionsIrrelevant(wasmBin)
+	}
+}
+
+// TestReRunFailedRequireNoDiffWithArgsCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedRequireNoDiffWithArgsCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	nodiff.RequireNoDiffWithArgs(wasmBin, argsFromSection(wasmBin), false, func(err error) { require.NoError(t, err) })
 }
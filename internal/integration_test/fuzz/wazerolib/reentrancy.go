@@ -0,0 +1,13 @@
+package main
+
+// The depths are kept out of extern.go so that the re-run tests, which are built without cgo, share them with the
+// fuzz targets.
+
+// reentrancyMaxDepth is the maximum depth of the nested calls re-entering the guest in require_no_diff_reentrant.
+const reentrancyMaxDepth = 8
+
+// deepReentrancyMaxDepth is the maximum depth of the nested calls re-entering the guest in
+// require_no_diff_deep_reentrant. Each re-entry runs on a fresh call engine with its own stack, so this never reaches
+// the stack overflow of the guest, but nests the host calls deeply on the Go stack. This must stay small enough for
+// the Go stack not to overflow with the interpreter, which calls the host functions recursively.
+const deepReentrancyMaxDepth = 1 << 8
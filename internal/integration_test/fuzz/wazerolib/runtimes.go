@@ -0,0 +1,111 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+)
+
+// requireIsolatedRuntimes ensures that two runtimes alive at the same time don't share state with each other.
+// The module is instantiated on two differently configured runtimes, whose invocations are interleaved, and one of
+// the runtimes is closed while the other is still in use. Then the state of each instance must be the same as the one
+// mutated alone on a fresh runtime in the same way.
+func requireIsolatedRuntimes(wasmBin []byte) {
+	ctx := context.Background()
+	configs := [2]wazero.RuntimeConfig{
+		wazero.NewRuntimeConfigCompiler(),
+		// The other runtime is configured differently so that any configuration leaking across runtimes is visible.
+		wazero.NewRuntimeConfigCompiler().WithMemoryCapacityFromMax(true),
+	}
+
+	a, err := newRuntimeSession(ctx, configs[0], wasmBin)
+	if err != nil {
+		return
+	}
+	defer a.close(ctx)
+	b, err := newRuntimeSession(ctx, configs[1], wasmBin)
+	if err != nil {
+		panic(fmt.Sprintf("failed only on the second runtime: %v", err))
+	}
+
+	a.invokeAll(ctx)
+	b.invokeAll(ctx)
+	a.invokeAll(ctx)
+	bState := b.state()
+	b.close(ctx)
+	// The first runtime must not be affected by closing the second one.
+	a.invokeAll(ctx)
+	aState := a.state()
+
+	for _, expected := range []struct {
+		config wazero.RuntimeConfig
+		rounds int
+		state  string
+	}{
+		{config: configs[0], rounds: 3, state: aState},
+		{config: configs[1], rounds: 1, state: bState},
+	} {
+		alone, err := newRuntimeSession(ctx, expected.config, wasmBin)
+		if err != nil {
+			panic(err)
+		}
+		for round := 0; round < expected.rounds; round++ {
+			alone.invokeAll(ctx)
+		}
+		state := alone.state()
+		alone.close(ctx)
+		if state != expected.state {
+			panic(fmt.Sprintf("runtimes are not isolated:\n%s\nvs alone:\n%s", expected.state, state))
+		}
+	}
+}
+
+// runtimeSession is the instance of the module on its own runtime, and the transcript of the invocations on it.
+type runtimeSession struct {
+	r          wazero.Runtime
+	mod        api.Module
+	names      []string
+	transcript strings.Builder
+}
+
+// newRuntimeSession instantiates wasmBin on a new runtime with config.
+func newRuntimeSession(ctx context.Context, config wazero.RuntimeConfig, wasmBin []byte) (*runtimeSession, error) {
+	r := wazero.NewRuntimeWithConfig(ctx, config.
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads))
+	mod, err := r.InstantiateWithConfig(ctx, wasmBin, wazero.NewModuleConfig().WithName(""))
+	if err != nil {
+		_ = r.Close(ctx)
+		return nil, err
+	}
+
+	defs := mod.ExportedFunctionDefinitions()
+	names := make([]string, 0, len(defs))
+	for name := range defs {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return &runtimeSession{r: r, mod: mod, names: names}, nil
+}
+
+// invokeAll invokes all the exported functions in order, and writes their results into the transcript.
+func (s *runtimeSession) invokeAll(ctx context.Context) {
+	for _, name := range s.names {
+		fmt.Fprintf(&s.transcript, "%q: %s\n", name, callWithDummyParams(ctx, s.mod.ExportedFunction(name)))
+	}
+}
+
+// state returns the transcript together with the state of the instance.
+func (s *runtimeSession) state() string {
+	return s.transcript.String() + instanceState(s.mod)
+}
+
+func (s *runtimeSession) close(ctx context.Context) {
+	if err := s.r.Close(ctx); err != nil {
+		panic(err)
+	}
+}
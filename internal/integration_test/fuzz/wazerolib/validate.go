@@ -2,16 +2,54 @@ package main
 
 import (
 	"context"
+	"fmt"
+	"sort"
+	"strings"
+	"time"
 
 	"github.com/tetratelabs/wazero"
 	"github.com/tetratelabs/wazero/api"
 	"github.com/tetratelabs/wazero/experimental"
+	"github.com/tetratelabs/wazero/internal/testing/nodiff"
 )
 
 // Ensure that validation and compilation do not panic!
 func tryCompile(wasmBin []byte) {
+	_ = compilationError(wazero.NewRuntimeConfigCompiler(), wasmBin)
+}
+
+// validModuleTimeout bounds the instantiation and the invocations in requireNoEnginePanic, as the mutated modules are
+// not guaranteed to terminate.
+const validModuleTimeout = 100 * time.Millisecond
+
+// requireSameValidation ensures that the compiler and the interpreter agree on whether the binary is valid, and that
+// neither engine panics on executing the binary if it is valid.
+func requireSameValidation(wasmBin []byte) {
+	if requireSameCompilationError(wasmBin) {
+		requireNoEnginePanic(wazero.NewRuntimeConfigCompiler(), wasmBin)
+		requireNoEnginePanic(wazero.NewRuntimeConfigInterpreter(), wasmBin)
+	}
+}
+
+// requireSameCompilationError ensures that the compiler and the interpreter agree on whether the binary is valid
+// with the same error, and returns true if the binary is valid.
+//
+// Note that both engines share the decoder and wasm.Module Validate, so this cannot catch the modules wrongly accepted
+// or rejected by the validation itself, but only the disagreement in the engine specific parts of the compilation.
+func requireSameCompilationError(wasmBin []byte) (valid bool) {
+	compilerErr := compilationError(wazero.NewRuntimeConfigCompiler(), wasmBin)
+	interpreterErr := compilationError(wazero.NewRuntimeConfigInterpreter(), wasmBin)
+	if (compilerErr == nil) != (interpreterErr == nil) ||
+		(compilerErr != nil && compilerErr.Error() != interpreterErr.Error()) {
+		panic(fmt.Sprintf("compilation error mismatch:\n\tinterpreter: %v\n\tcompiler: %v", interpreterErr, compilerErr))
+	}
+	return compilerErr == nil
+}
+
+// compilationError returns the error of compiling the binary with the given configuration.
+func compilationError(config wazero.RuntimeConfig, wasmBin []byte) error {
 	ctx := context.Background()
-	r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigCompiler().
+	r := wazero.NewRuntimeWithConfig(ctx, config.
 		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads))
 	defer func() {
 		if err := r.Close(context.Background()); err != nil {
@@ -24,4 +62,49 @@ func tryCompile(wasmBin []byte) {
 			panic(err)
 		}
 	}
+	return err
+}
+
+// requireNoEnginePanic ensures that the engine configured with config doesn't panic while instantiating the valid
+// binary and invoking its exported functions. Traps are expected on the mutated modules, so only the panics recovered
+// by wazero are reported.
+func requireNoEnginePanic(config wazero.RuntimeConfig, wasmBin []byte) {
+	ctx, cancel := context.WithTimeout(context.Background(), validModuleTimeout)
+	defer cancel()
+	r := wazero.NewRuntimeWithConfig(ctx, config.
+		WithCoreFeatures(api.CoreFeaturesV2|experimental.CoreFeaturesThreads).
+		WithCloseOnContextDone(true))
+	defer func() {
+		if err := r.Close(context.Background()); err != nil {
+			panic(err)
+		}
+	}()
+
+	requireNotRecovered := func(err error) {
+		if err != nil && strings.Contains(err.Error(), "(recovered by wazero)") {
+			panic(fmt.Sprintf("engine panicked on the valid module: %v", err))
+		}
+	}
+
+	compiled, err := r.CompileModule(ctx, wasmBin)
+	if err != nil {
+		// The binary has been compiled by requireSameCompilationError.
+		panic(err)
+	}
+	mod, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName(""))
+	requireNotRecovered(err)
+	if err != nil {
+		return
+	}
+
+	names := make([]string, 0, len(compiled.ExportedFunctions()))
+	for name := range compiled.ExportedFunctions() {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		f := mod.ExportedFunction(name)
+		_, err = f.Call(ctx, nodiff.DummyValues(f.Definition().ParamTypes())...)
+		requireNotRecovered(err)
+	}
 }
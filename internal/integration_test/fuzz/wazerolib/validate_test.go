@@ -16,3 +16,15 @@ func TestReRunFailedValidateCase(t *testing.T) {
 
 	tryCompile(wasmBin)
 }
+
+// TestReRunFailedSameValidationCase re-runs the failed case specified by WASM_BINARY_PATH in testdata directory.
+func TestReRunFailedSameValidationCase(t *testing.T) {
+	binaryPath := os.Getenv("WASM_BINARY_PATH")
+
+	wasmBin, err := os.ReadFile(binaryPath)
+	if err != nil {
+		t.Skip(err)
+	}
+
+	requireSameValidation(wasmBin)
+}
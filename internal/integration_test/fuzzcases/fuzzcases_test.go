@@ -992,28 +992,28 @@ func Test2078(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2078"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2078"), true, true, false)
 }
 
 func Test2082(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2082"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2082"), true, true, false)
 }
 
 func Test2084(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2084"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2084"), true, true, false)
 }
 
 func Test2096(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2096"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2096"), true, true, false)
 }
 
 func Test2097(t *testing.T) {
@@ -1043,47 +1043,47 @@ func Test2118(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2118"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2118"), true, true, false)
 }
 
 func Test2131(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2131"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2131"), true, true, false)
 }
 
 func Test2136(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2136"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2136"), true, true, false)
 }
 
 func Test2137(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2137"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2137"), true, true, false)
 }
 
 func Test2140(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2140"), true, true)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2140"), true, true, false)
 }
 
 func Test2201(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2201"), false, false)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2201"), false, false, false)
 }
 
 func Test2260(t *testing.T) {
 	if !platform.CompilerSupported() {
 		return
 	}
-	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2260"), false, false)
+	nodiff.RequireNoDiffT(t, getWasmBinary(t, "2260"), false, false, false)
 }
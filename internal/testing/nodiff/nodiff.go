@@ -3,6 +3,7 @@ package nodiff
 import (
 	"bytes"
 	"context"
+	"encoding/binary"
 	"errors"
 	"fmt"
 	"sort"
@@ -17,6 +18,7 @@ import (
 	"github.com/tetratelabs/wazero/internal/testing/binaryencoding"
 	"github.com/tetratelabs/wazero/internal/testing/require"
 	"github.com/tetratelabs/wazero/internal/wasm"
+	"github.com/tetratelabs/wazero/internal/wasmruntime"
 )
 
 // We haven't had public APIs for referencing all the imported entries from wazero.CompiledModule,
@@ -44,12 +46,52 @@ func extractInternalWasmModuleFromCompiledModule(c wazero.CompiledModule) (*wasm
 }
 
 // RequireNoDiffT is a wrapper of RequireNoDiff for testing.T.
-func RequireNoDiffT(t *testing.T, wasmBin []byte, checkMemory, loggingCheck bool) {
-	RequireNoDiff(wasmBin, checkMemory, loggingCheck, func(err error) { require.NoError(t, err) })
+func RequireNoDiffT(t *testing.T, wasmBin []byte, checkMemory, loggingCheck, trapReasonCheck bool) {
+	RequireNoDiff(wasmBin, checkMemory, loggingCheck, trapReasonCheck, func(err error) { require.NoError(t, err) })
 }
 
 // RequireNoDiff ensures that the behavior is the same between the compiler and the interpreter for any given binary.
-func RequireNoDiff(wasmBin []byte, checkMemory, loggingCheck bool, requireNoError func(err error)) {
+//
+// When trapReasonCheck is true, the trap reasons of failed invocations are compared in full, including the wasm stack
+// traces, instead of only the first line of the error messages.
+func RequireNoDiff(wasmBin []byte, checkMemory, loggingCheck, trapReasonCheck bool, requireNoError func(err error)) {
+	requireNoDiff(wasmBin, checkMemory, false, loggingCheck, trapReasonCheck, 0, nil, requireNoError)
+}
+
+// RequireNoDiffAfterTrap is the same as RequireNoDiff with checkMemory and trapReasonCheck except that the memory
+// contents are also compared right after each invocation which traps in both engines, so that the partial effects
+// before traps are also checked. The comparison after traps stops once either engine reaches the stack overflow,
+// as the depth at which it happens differs between engines.
+func RequireNoDiffAfterTrap(wasmBin []byte, requireNoError func(err error)) {
+	requireNoDiff(wasmBin, true, true, false, true, 0, nil, requireNoError)
+}
+
+// ReentrantHostModuleName is the name of the host module whose functions re-enter the guest in RequireNoDiffReentrant.
+const ReentrantHostModuleName = "host"
+
+// RequireNoDiffReentrant is the same as RequireNoDiff except that the functions imported from ReentrantHostModuleName
+// are implemented in Go, and re-enter the importing module by calling its exported functions up to maxDepth nested calls.
+func RequireNoDiffReentrant(wasmBin []byte, maxDepth int, requireNoError func(err error)) {
+	requireNoDiff(wasmBin, false, false, false, false, maxDepth, nil, requireNoError)
+}
+
+// maxArgumentRounds is the maximum number of rounds of the invocations in RequireNoDiffWithArgs.
+const maxArgumentRounds = 16
+
+// RequireNoDiffWithArgs is the same as RequireNoDiff except that, after the invocations with the dummy arguments,
+// the exported functions are invoked again in rounds with the arguments decoded from args until it is exhausted.
+// Each round runs on the fresh instances of the module.
+// The arguments of each type are read from args in little endian: 4 bytes for i32 and f32, 8 bytes for i64 and f64,
+// and 16 bytes for v128. References are always null.
+func RequireNoDiffWithArgs(wasmBin, args []byte, trapReasonCheck bool, requireNoError func(err error)) {
+	requireNoDiff(wasmBin, false, false, false, trapReasonCheck, 0, args, requireNoError)
+}
+
+// requireNoDiff implements RequireNoDiff, RequireNoDiffAfterTrap, RequireNoDiffReentrant and RequireNoDiffWithArgs.
+// checkMemoryAfterTrap is true if the memory is also compared after each trapping invocation. reentrancyDepth is zero
+// if the functions imported from ReentrantHostModuleName are not re-entrant. args is nil unless the functions are
+// invoked with the arguments decoded from it.
+func requireNoDiff(wasmBin []byte, checkMemory, checkMemoryAfterTrap, loggingCheck, trapReasonCheck bool, reentrancyDepth int, args []byte, requireNoError func(err error)) {
 	const features = api.CoreFeaturesV2 | experimental.CoreFeaturesThreads
 	compiler := wazero.NewRuntimeWithConfig(context.Background(), wazero.NewRuntimeConfigCompiler().WithCoreFeatures(features))
 	interpreter := wazero.NewRuntimeWithConfig(context.Background(), wazero.NewRuntimeConfigInterpreter().WithCoreFeatures(features))
@@ -85,10 +127,10 @@ func RequireNoDiff(wasmBin []byte, checkMemory, loggingCheck bool, requireNoErro
 	internalMod, err := extractInternalWasmModuleFromCompiledModule(compilerCompiled)
 	requireNoError(err)
 
-	if skip := ensureDummyImports(compiler, internalMod, requireNoError); skip {
+	if skip := ensureDummyImports(compiler, internalMod, reentrancyDepth, requireNoError); skip {
 		return
 	}
-	ensureDummyImports(interpreter, internalMod, requireNoError)
+	ensureDummyImports(interpreter, internalMod, reentrancyDepth, requireNoError)
 
 	// Instantiate module.
 	compilerMod, compilerInstErr := compiler.InstantiateModule(compilerCtx, compilerCompiled,
@@ -102,15 +144,36 @@ func RequireNoDiff(wasmBin []byte, checkMemory, loggingCheck bool, requireNoErro
 	if okToInvoke {
 		err, errorDuringInvocation = ensureInvocationResultMatch(
 			compilerCtx, interpreterCtx,
-			compilerMod, interpreterMod, interpreterCompiled.ExportedFunctions())
+			compilerMod, interpreterMod, interpreterCompiled.ExportedFunctions(), trapReasonCheck, checkMemoryAfterTrap, DummyValues)
 		requireNoError(err)
 
-		compilerMem, _ := compilerMod.Memory().(*wasm.MemoryInstance)
-		interpreterMem, _ := interpreterMod.Memory().(*wasm.MemoryInstance)
-		if checkMemory && compilerMem != nil && interpreterMem != nil {
-			if !bytes.Equal(compilerMem.Buffer, interpreterMem.Buffer) {
-				requireNoError(errors.New("memory state mimsmatch"))
+		for round := 0; round < maxArgumentRounds && len(args) > 0; round++ {
+			// Each round runs on the fresh instances, as otherwise the fuel global would often have been used up by the
+			// previous invocations, and every function would trap at its entry regardless of the arguments.
+			roundName := fmt.Sprintf("%s#%d", internalMod.ID[:], round)
+			compilerRoundMod, compilerInstErr := compiler.InstantiateModule(compilerCtx, compilerCompiled,
+				wazero.NewModuleConfig().WithName(roundName))
+			interpreterRoundMod, interpreterInstErr := interpreter.InstantiateModule(interpreterCtx, interpreterCompiled,
+				wazero.NewModuleConfig().WithName(roundName))
+			roundOK, err := ensureInstantiationError(compilerInstErr, interpreterInstErr)
+			requireNoError(err)
+			if !roundOK {
+				break
 			}
+
+			var errorDuringRound bool
+			err, errorDuringRound = ensureInvocationResultMatch(
+				compilerCtx, interpreterCtx,
+				compilerRoundMod, interpreterRoundMod, interpreterCompiled.ExportedFunctions(), trapReasonCheck, checkMemoryAfterTrap, argumentsFrom(&args))
+			errorDuringInvocation = errorDuringInvocation || errorDuringRound
+			requireNoError(err)
+			ensureMutableGlobalsMatch(compilerRoundMod, interpreterRoundMod, requireNoError)
+			requireNoError(compilerRoundMod.Close(compilerCtx))
+			requireNoError(interpreterRoundMod.Close(interpreterCtx))
+		}
+
+		if checkMemory {
+			requireNoError(ensureMemoryMatch(compilerMod, interpreterMod))
 		}
 		ensureMutableGlobalsMatch(compilerMod, interpreterMod, requireNoError)
 	}
@@ -160,7 +223,9 @@ func ensureMutableGlobalsMatch(compilerMod, interpreterMod api.Module, requireNo
 }
 
 // ensureDummyImports instantiates the modules which are required imports by `origin` *wasm.Module.
-func ensureDummyImports(r wazero.Runtime, origin *wasm.Module, requireNoError func(err error)) (skip bool) {
+//
+// When reentrancyDepth is positive, ReentrantHostModuleName is instantiated with instantiateReentrantHostModule instead.
+func ensureDummyImports(r wazero.Runtime, origin *wasm.Module, reentrancyDepth int, requireNoError func(err error)) (skip bool) {
 	impMods := make(map[string][]wasm.Import)
 	for _, imp := range origin.ImportSection {
 		if imp.Module == "" {
@@ -172,6 +237,15 @@ func ensureDummyImports(r wazero.Runtime, origin *wasm.Module, requireNoError fu
 	}
 
 	for mName, impMod := range impMods {
+		if reentrancyDepth > 0 && mName == ReentrantHostModuleName {
+			var err error
+			if skip, err = instantiateReentrantHostModule(r, origin, impMod, reentrancyDepth); skip {
+				return
+			}
+			requireNoError(err)
+			continue
+		}
+
 		usedName := make(map[string]struct{}, len(impMod))
 		m := &wasm.Module{NameSection: &wasm.NameSection{ModuleName: mName}}
 
@@ -266,12 +340,94 @@ func ensureDummyImports(r wazero.Runtime, origin *wasm.Module, requireNoError fu
 	return
 }
 
+// instantiateReentrantHostModule instantiates ReentrantHostModuleName which implements the function imports in impMod
+// with reentrantHostFunction. skip is true if impMod contains the imports which cannot be implemented this way.
+func instantiateReentrantHostModule(r wazero.Runtime, origin *wasm.Module, impMod []wasm.Import, maxDepth int) (skip bool, err error) {
+	b := r.NewHostModuleBuilder(ReentrantHostModuleName)
+	usedName := make(map[string]struct{}, len(impMod))
+	// depth is shared among all the functions in the module as they might re-enter each other through the guest.
+	var depth int
+	for _, imp := range impMod {
+		if _, ok := usedName[imp.Name]; ok || imp.Type != wasm.ExternTypeFunc {
+			// See the comment on the duplicated imports in ensureDummyImports.
+			return true, nil
+		}
+		usedName[imp.Name] = struct{}{}
+
+		tp := origin.TypeSection[imp.DescFunc]
+		if hasV128(tp.Params) || hasV128(tp.Results) {
+			// v128 occupies two stack slots, which is not handled by reentrantHostFunction for simplicity.
+			return true, nil
+		}
+		b.NewFunctionBuilder().
+			WithGoModuleFunction(reentrantHostFunction(&depth, maxDepth, tp), tp.Params, tp.Results).
+			Export(imp.Name)
+	}
+	_, err = b.Instantiate(context.Background())
+	return
+}
+
+// reentrantHostFunction returns the host function which re-enters the calling module by invoking its exported function
+// selected by the first parameter, unless depth has already reached maxDepth. The first result is taken from the
+// invoked function if the types match, and the rest are zeros.
+func reentrantHostFunction(depth *int, maxDepth int, tp wasm.FunctionType) api.GoModuleFunction {
+	return api.GoModuleFunc(func(ctx context.Context, m api.Module, stack []uint64) {
+		var selector uint64
+		if len(tp.Params) > 0 {
+			// Only use the lower 32-bits as the higher bits of 32-bit values are not guaranteed to be zero.
+			selector = uint64(uint32(stack[0]))
+		}
+		for i := range tp.Results {
+			stack[i] = 0
+		}
+
+		if *depth >= maxDepth {
+			return
+		}
+		defs := m.ExportedFunctionDefinitions()
+		if len(defs) == 0 {
+			return
+		}
+
+		// In order to do the deterministic execution, we need to sort the exported functions.
+		names := make([]string, 0, len(defs))
+		for name := range defs {
+			names = append(names, name)
+		}
+		sort.Strings(names)
+		name := names[selector%uint64(len(names))]
+		def := defs[name]
+
+		*depth++
+		defer func() { *depth-- }()
+		res, err := m.ExportedFunction(name).Call(ctx, DummyValues(def.ParamTypes())...)
+		if err != nil {
+			// Propagate the error to the guest calling this host function.
+			panic(err)
+		}
+		if len(tp.Results) > 0 && len(def.ResultTypes()) > 0 && def.ResultTypes()[0] == tp.Results[0] {
+			stack[0] = res[0]
+		}
+	})
+}
+
+// hasV128 returns true if valueTypes contains v128.
+func hasV128(valueTypes []wasm.ValueType) bool {
+	for _, vt := range valueTypes {
+		if vt == wasm.ValueTypeV128 {
+			return true
+		}
+	}
+	return false
+}
+
 const valueTypeVector = 0x7b
 
 // ensureInvocationResultMatch invokes all the exported functions from the module, and compare all the results between compiler vs interpreter.
 func ensureInvocationResultMatch(
 	compilerCtx, interpreterCtx context.Context, compiledMod, interpreterMod api.Module,
-	exportedFunctions map[string]api.FunctionDefinition,
+	exportedFunctions map[string]api.FunctionDefinition, trapReasonCheck, memoryCheckAfterTrap bool,
+	arguments func(paramTypes []api.ValueType) []uint64,
 ) (err error, errorDuringInvocation bool) {
 	// In order to do the deterministic execution, we need to sort the exported functions.
 	var names []string
@@ -280,23 +436,19 @@ func ensureInvocationResultMatch(
 	}
 	sort.Strings(names)
 
-outer:
+	var stackOverflow bool
 	for _, name := range names {
 		def := exportedFunctions[name]
-		resultTypes := def.ResultTypes()
-		for _, rt := range resultTypes {
-			switch rt {
-			case api.ValueTypeI32, api.ValueTypeI64, api.ValueTypeF32, api.ValueTypeF64, valueTypeVector:
-			default:
-				// For the sake of simplicity in the assertion, we only invoke the function with the basic types.
-				continue outer
-			}
+		if !HasBasicResults(def) {
+			// For the sake of simplicity in the assertion, we only invoke the function with the basic types.
+			continue
 		}
+		resultTypes := def.ResultTypes()
 
 		cmpF := compiledMod.ExportedFunction(name)
 		intF := interpreterMod.ExportedFunction(name)
 
-		params := getDummyValues(def.ParamTypes())
+		params := arguments(def.ParamTypes())
 		cmpRes, cmpErr := cmpF.Call(compilerCtx, params...)
 		intRes, intErr := intF.Call(interpreterCtx, params...)
 		errorDuringInvocation = errorDuringInvocation || cmpErr != nil || intErr != nil
@@ -304,6 +456,23 @@ outer:
 			err = errors.Join(err, fmt.Errorf("error mismatch on invoking %s: %v", name, errMismatch))
 			continue
 		}
+		if trapReasonCheck && cmpErr != nil && intErr != nil {
+			if errMismatch := ensureTrapReason(cmpErr, intErr); errMismatch != nil {
+				err = errors.Join(err, fmt.Errorf("trap reason mismatch on invoking %s: %v", name, errMismatch))
+				continue
+			}
+		}
+		if memoryCheckAfterTrap && cmpErr != nil && intErr != nil {
+			// The stores before the trap must have been made by both engines. Once either of them reaches stack overflow,
+			// the memory is no longer comparable as the depth at which it happens differs between engines.
+			stackOverflow = stackOverflow || isStackOverflow(cmpErr) || isStackOverflow(intErr)
+			if !stackOverflow {
+				if errMismatch := ensureMemoryMatch(compiledMod, interpreterMod); errMismatch != nil {
+					err = errors.Join(err, fmt.Errorf("%v after trap on invoking %s", errMismatch, name))
+					continue
+				}
+			}
+		}
 
 		matched := true
 		var typesIndex int
@@ -327,10 +496,53 @@ outer:
 	return
 }
 
-// getDummyValues returns a dummy input values for function invocations.
-func getDummyValues(valueTypes []api.ValueType) (ret []uint64) {
+// ensureMemoryMatch returns an error if the contents of the memory differ between the engines.
+func ensureMemoryMatch(compilerMod, interpreterMod api.Module) error {
+	compilerMem, _ := compilerMod.Memory().(*wasm.MemoryInstance)
+	interpreterMem, _ := interpreterMod.Memory().(*wasm.MemoryInstance)
+	if compilerMem != nil && interpreterMem != nil && !bytes.Equal(compilerMem.Buffer, interpreterMem.Buffer) {
+		return errors.New("memory state mimsmatch")
+	}
+	return nil
+}
+
+// isStackOverflow returns true if err is the stack overflow trap.
+func isStackOverflow(err error) bool {
+	var trap *wasmruntime.Error
+	return errors.As(err, &trap) && trap == wasmruntime.ErrRuntimeStackOverflow
+}
+
+// argumentsFrom returns the function which decodes the arguments of the given types from the head of *args,
+// and advances it. The missing bytes are regarded as zero once *args is exhausted.
+func argumentsFrom(args *[]byte) func(paramTypes []api.ValueType) []uint64 {
+	next := func(size int) uint64 {
+		var buf [8]byte
+		n := copy(buf[:size], *args)
+		*args = (*args)[n:]
+		return binary.LittleEndian.Uint64(buf[:])
+	}
+	return func(paramTypes []api.ValueType) (ret []uint64) {
+		for _, vt := range paramTypes {
+			switch vt {
+			case api.ValueTypeI32, api.ValueTypeF32:
+				ret = append(ret, next(4))
+			case api.ValueTypeI64, api.ValueTypeF64:
+				ret = append(ret, next(8))
+			case valueTypeVector:
+				ret = append(ret, next(8), next(8))
+			default:
+				// Arbitrary values cannot be references, so pass null.
+				ret = append(ret, 0)
+			}
+		}
+		return
+	}
+}
+
+// DummyValues returns a dummy input values for function invocations.
+func DummyValues(valueTypes []api.ValueType) (ret []uint64) {
 	for _, vt := range valueTypes {
-		if vt != 0x7b { // v128
+		if vt != valueTypeVector {
 			ret = append(ret, 0)
 		} else {
 			ret = append(ret, 0, 0)
@@ -339,6 +551,19 @@ func getDummyValues(valueTypes []api.ValueType) (ret []uint64) {
 	return
 }
 
+// HasBasicResults returns true if all the results of def are numbers or vectors, which are comparable between
+// engines and instances unlike references.
+func HasBasicResults(def api.FunctionDefinition) bool {
+	for _, rt := range def.ResultTypes() {
+		switch rt {
+		case api.ValueTypeI32, api.ValueTypeI64, api.ValueTypeF32, api.ValueTypeF64, valueTypeVector:
+		default:
+			return false
+		}
+	}
+	return true
+}
+
 // ensureInvocationError ensures that function invocation errors returned by interpreter and compiler match each other's.
 func ensureInvocationError(compilerErr, interpErr error) error {
 	if compilerErr == nil && interpErr == nil {
@@ -357,11 +582,17 @@ func ensureInvocationError(compilerErr, interpErr error) error {
 		interpErrMsg = interpErrMsg[:strings.Index(interpErrMsg, "\n")]
 	}
 
-	if compiledStackOverFlow := strings.Contains(compilerErrMsg, "stack overflow"); compiledStackOverFlow && strings.Contains(interpErrMsg, "unreachable") {
+	compiledStackOverFlow := strings.Contains(compilerErrMsg, "stack overflow")
+	interpreterStackOverFlow := strings.Contains(interpErrMsg, "stack overflow")
+	if compiledStackOverFlow && strings.Contains(interpErrMsg, "unreachable") {
 		// Compiler is more likely to reach stack overflow than interpreter, so we allow this case. This case is most likely
 		// that interpreter reached the unreachable out of "fuel".
 		return nil
-	} else if interpreterStackOverFlow := strings.Contains(interpErrMsg, "stack overflow"); compiledStackOverFlow && interpreterStackOverFlow {
+	} else if interpreterStackOverFlow && strings.Contains(compilerErrMsg, "unreachable") {
+		// On the other hand, the interpreter reaches stack overflow at the fixed call depth, while the compiler's stack
+		// can grow far deeper, so the compiler can run out of "fuel" first with the large fuel.
+		return nil
+	} else if compiledStackOverFlow && interpreterStackOverFlow {
 		// Both compiler and interpreter reached stack overflow, so we ignore diff in the content of the traces.
 		return nil
 	}
@@ -372,6 +603,33 @@ func ensureInvocationError(compilerErr, interpErr error) error {
 	return nil
 }
 
+// ensureTrapReason ensures that both the *wasmruntime.Error and the entire error messages, including the wasm stack traces,
+// returned by interpreter and compiler match each other's.
+func ensureTrapReason(compilerErr, interpErr error) error {
+	var compilerTrap, interpTrap *wasmruntime.Error
+	errors.As(compilerErr, &compilerTrap)
+	errors.As(interpErr, &interpTrap)
+
+	if compilerTrap == wasmruntime.ErrRuntimeStackOverflow || interpTrap == wasmruntime.ErrRuntimeStackOverflow {
+		// The depth at which stack overflow happens differs between engines, so the stack traces are not comparable.
+		return nil
+	}
+
+	if compilerTrap != interpTrap || compilerErr.Error() != interpErr.Error() {
+		return fmt.Errorf("trap reason mismatch:\n\tinterpreter: %s\n\tcompiler: %s",
+			describeTrap(interpTrap, interpErr), describeTrap(compilerTrap, compilerErr))
+	}
+	return nil
+}
+
+// describeTrap returns the human-readable description of the trap, including its *wasmruntime.Error if any.
+func describeTrap(trap *wasmruntime.Error, err error) string {
+	if trap == nil {
+		return fmt.Sprintf("(no trap code) %v", err)
+	}
+	return fmt.Sprintf("(%s) %v", trap.Error(), err)
+}
+
 // ensureInstantiationError ensures that instantiation errors returned by interpreter and compiler match each other's.
 func ensureInstantiationError(compilerErr, interpErr error) (okToInvoke bool, err error) {
 	if compilerErr == nil && interpErr == nil {
@@ -1,10 +1,17 @@
 package nodiff
 
 import (
+	"context"
+	"errors"
+	"fmt"
 	"testing"
 
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/internal/testing/binaryencoding"
 	"github.com/tetratelabs/wazero/internal/testing/require"
 	"github.com/tetratelabs/wazero/internal/wasm"
+	"github.com/tetratelabs/wazero/internal/wasmruntime"
 )
 
 func Test_ensureMutableGlobalsMatch(t *testing.T) {
@@ -203,3 +210,376 @@ func Test_ensureMutableGlobalsMatch(t *testing.T) {
 		})
 	}
 }
+
+func Test_ensureInvocationError(t *testing.T) {
+	for _, tc := range []struct {
+		name                   string
+		compilerErr, interpErr error
+		expErr                 string
+	}{
+		{
+			name: "no error",
+		},
+		{
+			name:        "same error",
+			compilerErr: errors.New("wasm error: unreachable\nwasm stack trace:\n\t.$0()"),
+			interpErr:   errors.New("wasm error: unreachable\nwasm stack trace:\n\t.$1()"),
+		},
+		{
+			name:        "both stack overflow",
+			compilerErr: errors.New("wasm error: stack overflow"),
+			interpErr:   errors.New("wasm error: stack overflow"),
+		},
+		{
+			name:        "compiler stack overflow",
+			compilerErr: errors.New("wasm error: stack overflow"),
+			interpErr:   errors.New("wasm error: unreachable"),
+		},
+		{
+			name:        "interpreter stack overflow",
+			compilerErr: errors.New("wasm error: unreachable"),
+			interpErr:   errors.New("wasm error: stack overflow"),
+		},
+		{
+			name:        "interpreter stack overflow on other trap",
+			compilerErr: errors.New("wasm error: integer divide by zero"),
+			interpErr:   errors.New("wasm error: stack overflow"),
+			expErr: `error mismatch:
+	interpreter: wasm error: stack overflow
+	compiler: wasm error: integer divide by zero`,
+		},
+		{
+			name:      "compiler no error",
+			interpErr: errors.New("wasm error: stack overflow"),
+			expErr:    "compiler returned no error, but interpreter got: wasm error: stack overflow",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			err := ensureInvocationError(tc.compilerErr, tc.interpErr)
+			if tc.expErr == "" {
+				require.NoError(t, err)
+			} else {
+				require.EqualError(t, err, tc.expErr)
+			}
+		})
+	}
+}
+
+func Test_ensureTrapReason(t *testing.T) {
+	wasmErr := func(trap *wasmruntime.Error, trace string) error {
+		return fmt.Errorf("wasm error: %w\nwasm stack trace:\n\t%s", trap, trace)
+	}
+
+	for _, tc := range []struct {
+		name                   string
+		compilerErr, interpErr error
+		expErr                 string
+	}{
+		{
+			name:        "match",
+			compilerErr: wasmErr(wasmruntime.ErrRuntimeUnreachable, ".$0()"),
+			interpErr:   wasmErr(wasmruntime.ErrRuntimeUnreachable, ".$0()"),
+		},
+		{
+			name:        "stack overflow",
+			compilerErr: wasmErr(wasmruntime.ErrRuntimeStackOverflow, ".$0()\n\t.$0()"),
+			interpErr:   wasmErr(wasmruntime.ErrRuntimeStackOverflow, ".$0()"),
+		},
+		{
+			name:        "compiler stack overflow",
+			compilerErr: wasmErr(wasmruntime.ErrRuntimeStackOverflow, ".$0()"),
+			interpErr:   wasmErr(wasmruntime.ErrRuntimeUnreachable, ".$0()"),
+		},
+		{
+			name:        "trace mismatch",
+			compilerErr: wasmErr(wasmruntime.ErrRuntimeUnreachable, ".$0()"),
+			interpErr:   wasmErr(wasmruntime.ErrRuntimeUnreachable, ".$1()"),
+			expErr: `trap reason mismatch:
+	interpreter: (unreachable) wasm error: unreachable
+wasm stack trace:
+	.$1()
+	compiler: (unreachable) wasm error: unreachable
+wasm stack trace:
+	.$0()`,
+		},
+		{
+			name:        "trap code mismatch",
+			compilerErr: errors.New("wasm error: unreachable"),
+			interpErr:   wasmErr(wasmruntime.ErrRuntimeUnreachable, ".$0()"),
+			expErr: `trap reason mismatch:
+	interpreter: (unreachable) wasm error: unreachable
+wasm stack trace:
+	.$0()
+	compiler: (no trap code) wasm error: unreachable`,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			err := ensureTrapReason(tc.compilerErr, tc.interpErr)
+			if tc.expErr == "" {
+				require.NoError(t, err)
+			} else {
+				require.EqualError(t, err, tc.expErr)
+			}
+		})
+	}
+}
+
+func Test_argumentsFrom(t *testing.T) {
+	args := []byte{
+		1, 0, 0, 0, // i32
+		2, 0, 0, 0, 0, 0, 0, 0x80, // i64
+		3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, // v128
+		5, 6, // f32, truncated.
+	}
+	arguments := argumentsFrom(&args)
+
+	require.Equal(t, []uint64{1, 0x8000000000000002, 0}, arguments([]api.ValueType{api.ValueTypeI32, api.ValueTypeI64, api.ValueTypeExternref}))
+	require.Equal(t, 18, len(args))
+	require.Equal(t, []uint64{3, 4, 0x0605}, arguments([]api.ValueType{valueTypeVector, api.ValueTypeF32}))
+	require.Equal(t, 0, len(args))
+	require.Equal(t, []uint64{0}, arguments([]api.ValueType{api.ValueTypeF64}))
+}
+
+func Test_ensureInvocationResultMatch_memoryCheckAfterTrap(t *testing.T) {
+	bin := binaryencoding.EncodeModule(&wasm.Module{
+		TypeSection:     []wasm.FunctionType{{}},
+		FunctionSection: []wasm.Index{0, 0},
+		MemorySection:   &wasm.Memory{Min: 1, Cap: 1, Max: 1, IsMaxEncoded: true},
+		CodeSection: []wasm.Code{
+			{Body: []byte{wasm.OpcodeCall, 0, wasm.OpcodeEnd}},
+			{Body: []byte{
+				wasm.OpcodeI32Const, 0, wasm.OpcodeI32Const, 42, wasm.OpcodeI32Store8, 0, 0,
+				wasm.OpcodeUnreachable, wasm.OpcodeEnd,
+			}},
+		},
+		ExportSection: []wasm.Export{
+			{Type: wasm.ExternTypeFunc, Name: "overflow", Index: 0},
+			{Type: wasm.ExternTypeFunc, Name: "store_then_trap", Index: 1},
+		},
+	})
+
+	for _, tc := range []struct {
+		name                 string
+		exports              []string
+		memoryCheckAfterTrap bool
+		diverged             bool
+		expErr               string
+	}{
+		{
+			name:                 "match",
+			exports:              []string{"store_then_trap"},
+			memoryCheckAfterTrap: true,
+		},
+		{
+			name:                 "mismatch",
+			exports:              []string{"store_then_trap"},
+			memoryCheckAfterTrap: true,
+			diverged:             true,
+			expErr:               "memory state mimsmatch after trap on invoking store_then_trap",
+		},
+		{
+			name:     "mismatch without check",
+			exports:  []string{"store_then_trap"},
+			diverged: true,
+		},
+		{
+			name:                 "mismatch after stack overflow",
+			exports:              []string{"overflow", "store_then_trap"},
+			memoryCheckAfterTrap: true,
+			diverged:             true,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			ctx := context.Background()
+			r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigInterpreter())
+			defer r.Close(ctx)
+
+			compiled, err := r.CompileModule(ctx, bin)
+			require.NoError(t, err)
+			cm, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName("compiler"))
+			require.NoError(t, err)
+			im, err := r.InstantiateModule(ctx, compiled, wazero.NewModuleConfig().WithName("interpreter"))
+			require.NoError(t, err)
+			if tc.diverged {
+				// The byte which is not written by any function.
+				require.True(t, im.Memory().WriteByte(1, 1))
+			}
+
+			exportedFunctions := map[string]api.FunctionDefinition{}
+			for _, name := range tc.exports {
+				exportedFunctions[name] = compiled.ExportedFunctions()[name]
+			}
+			err, errorDuringInvocation := ensureInvocationResultMatch(ctx, ctx, cm, im, exportedFunctions,
+				false, tc.memoryCheckAfterTrap, DummyValues)
+			require.True(t, errorDuringInvocation)
+			if tc.expErr == "" {
+				require.NoError(t, err)
+			} else {
+				require.EqualError(t, err, tc.expErr)
+			}
+		})
+	}
+}
+
+func Test_instantiateReentrantHostModule(t *testing.T) {
+	i32ToI32 := wasm.FunctionType{Params: []wasm.ValueType{wasm.ValueTypeI32}, Results: []wasm.ValueType{wasm.ValueTypeI32}}
+	v128ToI32 := wasm.FunctionType{Params: []wasm.ValueType{wasm.ValueTypeV128}, Results: []wasm.ValueType{wasm.ValueTypeI32}}
+	i32ToV128 := wasm.FunctionType{Params: []wasm.ValueType{wasm.ValueTypeI32}, Results: []wasm.ValueType{wasm.ValueTypeV128}}
+
+	for _, tc := range []struct {
+		name    string
+		imports []wasm.Import
+		expSkip bool
+	}{
+		{
+			name: "ok",
+			imports: []wasm.Import{
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "a", DescFunc: 0},
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "b", DescFunc: 0},
+			},
+		},
+		{
+			name: "duplicated",
+			imports: []wasm.Import{
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "a", DescFunc: 0},
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "a", DescFunc: 0},
+			},
+			expSkip: true,
+		},
+		{
+			name: "v128 param",
+			imports: []wasm.Import{
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "a", DescFunc: 1},
+			},
+			expSkip: true,
+		},
+		{
+			name: "v128 result",
+			imports: []wasm.Import{
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "a", DescFunc: 2},
+			},
+			expSkip: true,
+		},
+		{
+			name: "not function",
+			imports: []wasm.Import{
+				{Type: wasm.ExternTypeGlobal, Module: ReentrantHostModuleName, Name: "a", DescGlobal: wasm.GlobalType{ValType: wasm.ValueTypeI32}},
+			},
+			expSkip: true,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			ctx := context.Background()
+			r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigInterpreter())
+			defer r.Close(ctx)
+
+			origin := &wasm.Module{TypeSection: []wasm.FunctionType{i32ToI32, v128ToI32, i32ToV128}, ImportSection: tc.imports}
+			skip, err := instantiateReentrantHostModule(r, origin, tc.imports, 1)
+			require.NoError(t, err)
+			require.Equal(t, tc.expSkip, skip)
+			// The host module is instantiated only when it is not skipped.
+			require.Equal(t, !tc.expSkip, r.Module(ReentrantHostModuleName) != nil)
+		})
+	}
+}
+
+func Test_reentrantHostFunction(t *testing.T) {
+	t.Run("depth", func(t *testing.T) {
+		i32ToI32 := wasm.FunctionType{Params: []wasm.ValueType{wasm.ValueTypeI32}, Results: []wasm.ValueType{wasm.ValueTypeI32}}
+		origin := &wasm.Module{
+			TypeSection: []wasm.FunctionType{i32ToI32, {Results: []wasm.ValueType{wasm.ValueTypeI32}}},
+			ImportSection: []wasm.Import{
+				{Type: wasm.ExternTypeFunc, Module: ReentrantHostModuleName, Name: "reenter", DescFunc: 0},
+			},
+			FunctionSection: []wasm.Index{1},
+			GlobalSection: []wasm.Global{
+				{
+					Type: wasm.GlobalType{ValType: wasm.ValueTypeI32, Mutable: true},
+					Init: wasm.ConstantExpression{Opcode: wasm.OpcodeI32Const, Data: []byte{0}},
+				},
+			},
+			// Counts the invocations in the global, then re-enters the guest through the host.
+			CodeSection: []wasm.Code{{Body: []byte{
+				wasm.OpcodeGlobalGet, 0, wasm.OpcodeI32Const, 1, wasm.OpcodeI32Add, wasm.OpcodeGlobalSet, 0,
+				wasm.OpcodeI32Const, 0, wasm.OpcodeCall, 0, wasm.OpcodeEnd,
+			}}},
+			ExportSection: []wasm.Export{
+				{Type: wasm.ExternTypeFunc, Name: "count_then_reenter", Index: 1},
+				{Type: wasm.ExternTypeGlobal, Name: "count", Index: 0},
+			},
+		}
+
+		ctx := context.Background()
+		r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigInterpreter())
+		defer r.Close(ctx)
+
+		const maxDepth = 3
+		skip, err := instantiateReentrantHostModule(r, origin, origin.ImportSection, maxDepth)
+		require.NoError(t, err)
+		require.False(t, skip)
+		mod, err := r.Instantiate(ctx, binaryencoding.EncodeModule(origin))
+		require.NoError(t, err)
+
+		_, err = mod.ExportedFunction("count_then_reenter").Call(ctx)
+		require.NoError(t, err)
+		// The outermost invocation plus maxDepth re-entries.
+		require.Equal(t, uint64(maxDepth+1), mod.ExportedGlobal("count").Get())
+
+		// The depth is restored after returning to the outermost invocation.
+		_, err = mod.ExportedFunction("count_then_reenter").Call(ctx)
+		require.NoError(t, err)
+		require.Equal(t, uint64(2*(maxDepth+1)), mod.ExportedGlobal("count").Get())
+	})
+
+	t.Run("selection", func(t *testing.T) {
+		ctx := context.Background()
+		r := wazero.NewRuntimeWithConfig(ctx, wazero.NewRuntimeConfigInterpreter())
+		defer r.Close(ctx)
+
+		// The exported functions are sorted by their names, so the selectors 0, 1 and 2 invoke "a", "b" and "c".
+		mod, err := r.Instantiate(ctx, binaryencoding.EncodeModule(&wasm.Module{
+			TypeSection: []wasm.FunctionType{
+				{Results: []wasm.ValueType{wasm.ValueTypeI32}},
+				{Results: []wasm.ValueType{wasm.ValueTypeI64}},
+			},
+			FunctionSection: []wasm.Index{1, 0, 0},
+			CodeSection: []wasm.Code{
+				{Body: []byte{wasm.OpcodeI64Const, 20, wasm.OpcodeEnd}},
+				{Body: []byte{wasm.OpcodeI32Const, 30, wasm.OpcodeEnd}},
+				{Body: []byte{wasm.OpcodeI32Const, 10, wasm.OpcodeEnd}},
+			},
+			ExportSection: []wasm.Export{
+				{Type: wasm.ExternTypeFunc, Name: "b", Index: 0},
+				{Type: wasm.ExternTypeFunc, Name: "c", Index: 1},
+				{Type: wasm.ExternTypeFunc, Name: "a", Index: 2},
+			},
+		}))
+		require.NoError(t, err)
+
+		tp := wasm.FunctionType{
+			Params:  []wasm.ValueType{wasm.ValueTypeI64},
+			Results: []wasm.ValueType{wasm.ValueTypeI32, wasm.ValueTypeI64},
+		}
+		for _, tc := range []struct {
+			name     string
+			selector uint64
+			depth    int
+			exp      []uint64
+		}{
+			{name: "first", selector: 0, exp: []uint64{10, 0}},
+			{name: "result type mismatch", selector: 1, exp: []uint64{0, 0}},
+			{name: "last", selector: 2, exp: []uint64{30, 0}},
+			{name: "wrap around", selector: 3, exp: []uint64{10, 0}},
+			{name: "lower 32 bits", selector: 0xffffffff_00000002, exp: []uint64{30, 0}},
+			{name: "max depth", selector: 2, depth: 1, exp: []uint64{0, 0}},
+		} {
+			t.Run(tc.name, func(t *testing.T) {
+				depth := tc.depth
+				stack := []uint64{tc.selector, 0xffffffff}
+				reentrantHostFunction(&depth, 1, tp).Call(ctx, mod, stack)
+				require.Equal(t, tc.exp, stack)
+				require.Equal(t, tc.depth, depth)
+			})
+		}
+	})
+}